@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use light_bulb::*;
 use typestate::typestate;
 
@@ -40,5 +42,5 @@ impl OnState for LightBulb<On> {
 fn main() {
     let bulb = LightBulb::<Off>::screw();
     let bulb = bulb.turn_on();
-    let bulb = bulb.turn_off();
+    let _bulb = bulb.turn_off();
 }
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 // use maintenance::*;
 use traffic_light::*;
 use typestate::typestate;
@@ -68,6 +68,15 @@
 //! - Functions that *do not* take a valid state (i.e. `self`) and return a valid state, describe an initial state.
 //! - Functions that take a valid state (i.e. `self`) and *do not* return a valid state, describe a final state.
 //!
+//! A state can also be explicitly marked as final by attaching `#[final_state]` next to `#[state]`,
+//! this is useful for states which are a legitimate dead-end of the protocol.
+//!
+//! Initial states can also be described by fallible functions, returning a `Result` with a valid state as the `Ok` type,
+//! which is useful to validate the arguments before constructing the automaton:
+//! ```rust,ignore
+//! fn new(location: Location) -> Result<Grounded, ConstructionError>;
+//! ```
+//!
 //! So we write the following function signatures:
 //! ```rust,ignore
 //! fn turn_on() -> Red;
@@ -180,6 +189,31 @@
 //! As these functions do not change the typestate state,
 //! they transition back to the current state.
 //!
+//! Transitions can be annotated with `#[metadata(weight = ...)]`, useful to model probabilistic protocols;
+//! the weight is appended to the transition label in the diagrams (e.g. `takeoff (0.8)`).
+//! Transitions can also be categorized with `#[metadata(color = "...")]`, which colors their edges (DOT and PlantUML only);
+//! the color is either a name (e.g. `red`) or a hex code (e.g. `#ff0000`).
+//! The transition name shown in the diagrams can be replaced with `#[metadata(label = "...")]`,
+//! and a note can be attached to the transition with `#[metadata(note = "...")]` (PlantUML only).
+//! The `priority` and `guard` keys only apply to the branches of non-deterministic transitions (see below) and are rejected on functions.
+//!
+//! Transitions between states can be guarded by a predicate, using `#[transition(guard = "path")]`,
+//! where the predicate is a function like `fn(&Automaton<State>) -> bool`.
+//! Along with the transition, a `try_` version of it is generated;
+//! it only proceeds if the predicate holds, otherwise the automaton is returned in the current state:
+//! ```rust,ignore
+//! trait Idle {
+//!     #[transition(guard = "battery_ok")]
+//!     fn take_off(self) -> Flying;
+//! }
+//!
+//! // generated, with the signature
+//! // fn try_take_off(self) -> Result<Drone<Flying>, Drone<Idle>>
+//! ```
+//!
+//! A half-written transition can be temporarily disabled with `#[transition(ignore)]`,
+//! the method is removed from the state trait and the automaton, while its declaration stays in place.
+//!
 //! ### Non-deterministic transitions
 //! Consider that a typestate relies on an external component that can fail, to model that, one would use `Result<T>`.
 //! However, we need our typestate to transition between known states, so we declare two things:
@@ -197,6 +231,31 @@
 //! ```
 //!
 //! Inside the enumeration there can only be other valid states and only `Unit` style variants are supported.
+//! In the diagrams, these enumerations are rendered as choice pseudo-states (`<<choice>>` in PlantUML and Mermaid, a diamond in DOT).
+//!
+//! The variants can be annotated with `#[metadata(...)]` to customize how each branch is rendered in the diagrams:
+//! - `label = "..."` - the branch label.
+//! - `note = "..."` - a note attached to the branch (PlantUML only).
+//! - `guard = "..."` - the condition under which the branch is taken, appended to the branch label as `[...]`.
+//! - `color = "..."` - the branch edge color, branches without one are not colored (DOT and PlantUML only).
+//! - `priority = N` - the order in which the branch is resolved, branches are rendered in ascending priority
+//!   and branches without a priority come last, in declaration order.
+//!
+//! The `weight` key only applies to transitions and is rejected on variants.
+//!
+//! ### Cross-module transitions
+//! Large protocols can be split across several `#[typestate]` modules.
+//! A state can transition into a state of another typestate module by returning the other automaton,
+//! written with its full path:
+//!
+//! ```rust,ignore
+//! trait Hello {
+//!     fn established(self) -> crate::session::Session<crate::session::Open>;
+//! }
+//! ```
+//!
+//! For the current module such a function consumes the state, and thus describes a final state.
+//! The target state is validated by the other module's automaton, which only accepts its own states.
 //!
 //! ## Attributes
 //!
@@ -208,15 +267,53 @@
 //!     that string will be used as identifier to the `enum`.
 //!   - If the parameter is used with an *empty string* or *without* a string, the default behavior is to prepend an `E` to the
 //! - `#[typestate(state_constructors = "...")`: this option generates basic constructors for states with fields.
+//! - `#[typestate(initial_constructors = "...")]`: this option generates a constructor for each initial state,
+//!   e.g. `TrafficLight::<Red>::new(cycles)`, taking the `#[automaton]` fields and the state itself (only when it has fields).
+//!   If the parameter is used with an *empty string* or *without* a string, the constructor is named `new`.
+//!   An initial state whose trait already declares a function with that name (e.g. a fallible `new`) is reported as an error, instead of being shadowed.
+//! - `#[typestate(shared_fields)]`: this option lists the `#[automaton]` fields (name and type) as a note in the generated diagrams,
+//!   documenting the data carried through every state.
+//! - `#[typestate(embed_automaton)]`: this option generates a `pub const AUTOMATON: &str` inside the module,
+//!   containing a JSON description of the states, choices and transitions (and the `shared_fields`, if listed), along with their counts under `stats`
+//!   (the `#[final_state]` entries of the transitions are counted apart, under `final_states`),
+//!   allowing tools to inspect the automaton at runtime.
+//! - `#[typestate(generate_transition_enum)]`: this option generates, for each state, an enumeration of its outgoing transitions
+//!   (e.g. `GroundedTransition::TakeOff` for `fn take_off`) along with a `transitions` method returning the available variants.
+//! - `#[typestate(transition_table)]`: this option generates a `StateId` enumeration, with a variant per state,
+//!   and a `transition_table` function returning the transitions between states as `(StateId, &str, StateId)` tuples,
+//!   allowing runtime code to introspect the protocol (transitions into an `enum` are listed once per variant).
+//! - `#[typestate(generate_from)]`: this option generates, for each transition which only takes `self` and has no guard,
+//!   a `From` implementation calling the transition (e.g. `let flying: Drone<Flying> = idle.into()` calls `take_off`),
+//!   allowing transitions to be used in generic contexts (self-loops, transitions into an `enum`
+//!   and states joined by several such transitions are skipped).
 //!
 //! ## Features
 //! The cargo features you can enable:
+//! - `mermaid-docs` will generate [Mermaid.js](https://mermaid-js.github.io/mermaid/#/) state diagrams embedded in your documentation. This feature is set by default.
+//!   - This feature can be customized through the following environment variables:
+//!     - `MERMAID_DIRECTION` - The diagram direction (e.g. `LR` for left to right), by default top to bottom. Also applies to `export-html`.
+//!     - `MERMAID_ACCEPTING_STYLE` - If set, the accepting states (the ones which can end the automaton) are styled with it (e.g. `fill:#9f9`), making them distinct beyond the termination arrow.
 //! - `debug_dot` will generate a `.dot` file of your state machine.
 //!   - This feature can be customized through the following environment variables (taken from the [DOT documentation](https://graphviz.org/doc/info/attrs.html)):
 //!     - `DOT_PAD` - Specifies how much, in inches, to extend the drawing area around the minimal area needed to draw the graph.
 //!     - `DOT_NODESEP` - In `dot`, `nodesep` specifies the minimum space between two adjacent nodes in the same rank, in inches.
 //!     - `DOT_RANKSEP` - In `dot`, sets the desired rank separation, in inches.
+//!     - `DOT_CONCENTRATE` - If `true`, merges multiedges into a single edge, reducing the clutter of dense diagrams (disabled by default).
+//!     - `DOT_LABEL_MODE` - Where transition labels are written: `inline` on the edge (default), as a `tooltip` shown on hover in SVG output, or `both`.
+//!     - `DOT_RANKDIR` - Sets the direction of the graph layout (e.g. `LR` for left-to-right), useful for sequential protocols.
+//!     - `DOT_ORDERING_EDGES` - If `true`, chains the states with invisible edges in breadth-first order, keeping them in transition order (disabled by default).
+//!     - `DOT_RANK_BY_DEPTH` - If `true`, places the states at the same breadth-first depth from the initial state on the same rank, laying the diagram out in layers (disabled by default).
+//!     - `DOT_SHADE_UNREACHABLE` - If `true`, shades gray the states that cannot be reached from an initial state, as the file is written before the automaton is validated (disabled by default).
 //! - `debug_plantuml` will generate a PlantUML state diagram (`.uml` file) of your state machine.
+//!   - This feature can be customized through the following environment variables (taken from the [PlantUML Hitchhiker's Guide](https://crashedmind.github.io/PlantUMLHitchhikersGuide/layout/layout.html#nodesep-and-ranksep))
+//!     - `PLANTUML_NODESEP` - `nodesep` specifies the minimum space between two adjacent nodes in the same rank.
+//!     - `PLANTUML_RANKSEP` - Sets the desired rank separation.
+//!     - `PLANTUML_THEME` - A preset `skinparam` theme, either `dark` or `minimal`; by default, no theme is applied.
+//! - `export-html` will generate a self-contained HTML page (`.html` file) rendering the Mermaid state diagram of your state machine,
+//!   useful to share the diagram with people who do not build the documentation.
+//!   - This feature can be customized through the following environment variables:
+//!     - `HTML_MERMAID_SRC` - The location Mermaid.js is loaded from, by default a CDN; point it to a local copy to view the page offline.
+//!     - `EXPORT_FOLDER` - Declare the target folder for exported files.
 
 pub extern crate typestate_proc_macro;
 
@@ -0,0 +1,31 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod handshake {
+    #[automaton]
+    pub struct Handshake;
+
+    #[state]
+    pub struct Hello;
+
+    pub trait Hello {
+        fn start() -> Hello;
+        fn established(self) -> crate::session::Session<Hello>;
+    }
+}
+
+#[typestate]
+mod session {
+    #[automaton]
+    pub struct Session;
+
+    #[state]
+    pub struct Open;
+
+    pub trait Open {
+        fn open() -> Open;
+        fn close(self);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,58 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(generate_from)]
+mod drone {
+    #[automaton]
+    pub struct Drone {
+        pub battery: u8,
+    }
+
+    #[state]
+    pub struct Idle;
+
+    #[state]
+    pub struct Flying;
+
+    pub trait Idle {
+        fn new() -> Idle;
+        #[transition(guard = "super::charged")]
+        fn take_off(self) -> Flying;
+        fn shutdown(self);
+    }
+
+    pub trait Flying {
+        fn land(self, battery: u8) -> Idle;
+    }
+}
+
+use drone::*;
+
+fn charged(drone: &Drone<Idle>) -> bool {
+    drone.battery > 10
+}
+
+impl IdleState for Drone<Idle> {
+    fn new() -> Self {
+        Self {
+            battery: 100,
+            state: Idle,
+        }
+    }
+
+    fn take_off(self) -> Drone<Flying> {
+        Drone {
+            battery: self.battery,
+            state: Flying,
+        }
+    }
+
+    fn shutdown(self) {}
+}
+
+fn convert(idle: Drone<Idle>, flying: Drone<Flying>) {
+    // guarded transitions and transitions taking arguments have no `From` implementation
+    let _: Drone<Flying> = idle.into();
+    let _: Drone<Idle> = flying.into();
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod drone {
+    #[automaton]
+    pub struct Drone {
+        pub battery: u8,
+    }
+
+    #[state]
+    pub struct Idle;
+
+    #[state]
+    pub struct Flying;
+
+    pub trait Idle {
+        fn start(battery: u8) -> Idle;
+        #[transition(guard = "super::battery_ok")]
+        fn take_off(self) -> Flying;
+        fn try_take_off(self) -> Flying;
+        fn stop(self);
+    }
+
+    pub trait Flying {
+        fn land(self) -> Idle;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+use typestate_proc_macro::typestate;
+
+pub struct ConstructionError;
+
+#[typestate(initial_constructors)]
+mod drone {
+    use super::ConstructionError;
+
+    #[automaton]
+    pub struct Drone {
+        pub location: u32,
+    }
+
+    #[state]
+    pub struct Grounded;
+
+    pub trait Grounded {
+        fn new(location: u32) -> Result<Grounded, ConstructionError>;
+        fn stop(self);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod m {
+    #[automaton]
+    pub struct M;
+
+    #[state]
+    pub struct A;
+
+    pub trait A {
+        fn start() -> A;
+        #[metadata(color = "red]-> A")]
+        fn next(self) -> A;
+        fn end(self);
+    }
+}
+
+fn main() {}
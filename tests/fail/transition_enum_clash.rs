@@ -0,0 +1,26 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(generate_transition_enum)]
+mod drone {
+    #[automaton]
+    pub struct Drone;
+
+    #[state]
+    pub struct Grounded;
+
+    #[state]
+    pub struct GroundedTransition;
+
+    pub trait Grounded {
+        fn start() -> Grounded;
+        fn prepare(self) -> GroundedTransition;
+        fn transitions(&self) -> u8;
+        fn stop(self);
+    }
+
+    pub trait GroundedTransition {
+        fn cancel(self) -> Grounded;
+    }
+}
+
+fn main() {}
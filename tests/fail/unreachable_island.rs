@@ -0,0 +1,40 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod door {
+    #[automaton]
+    pub struct Door;
+
+    #[state]
+    pub struct Open;
+
+    #[state]
+    pub struct Closed;
+
+    #[state]
+    pub struct Locked;
+
+    #[state]
+    pub struct Jammed;
+
+    pub trait Open {
+        fn new() -> Open;
+        fn close(self) -> Closed;
+    }
+
+    pub trait Closed {
+        fn open(self) -> Open;
+        fn remove(self);
+    }
+
+    pub trait Locked {
+        fn jam(self) -> Jammed;
+        fn discard(self);
+    }
+
+    pub trait Jammed {
+        fn unjam(self) -> Locked;
+    }
+}
+
+fn main() {}
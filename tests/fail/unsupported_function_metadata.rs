@@ -0,0 +1,30 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod m {
+    #[automaton]
+    pub struct M;
+
+    #[state]
+    pub struct A;
+
+    #[state]
+    pub struct B;
+
+    pub trait A {
+        fn start() -> A;
+        #[metadata(label = "go", priority = 1, guard = "ready")]
+        fn next(self) -> Either;
+    }
+
+    pub trait B {
+        fn end(self);
+    }
+
+    pub enum Either {
+        A,
+        B,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod m {
+    #[automaton]
+    pub struct M;
+
+    #[state]
+    pub struct A;
+
+    pub trait A {
+        #[transition(guard = "super::always")]
+        fn start() -> A;
+        fn end(self);
+    }
+}
+
+fn always<T>(_: &T) -> bool {
+    true
+}
+
+fn main() {}
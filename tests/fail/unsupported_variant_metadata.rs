@@ -0,0 +1,30 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod m {
+    #[automaton]
+    pub struct M;
+
+    #[state]
+    pub struct A;
+
+    #[state]
+    pub struct B;
+
+    pub trait A {
+        fn start() -> A;
+        fn next(self) -> Either;
+    }
+
+    pub trait B {
+        fn end(self);
+    }
+
+    pub enum Either {
+        #[metadata(weight = 0.5)]
+        A,
+        B,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,55 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod handshake {
+    #[automaton]
+    pub struct Handshake;
+
+    #[state]
+    pub struct Hello;
+
+    pub trait Hello {
+        fn start() -> Hello;
+        fn established(self) -> crate::session::Session<crate::session::Open>;
+    }
+}
+
+#[typestate]
+mod session {
+    #[automaton]
+    pub struct Session;
+
+    #[state]
+    pub struct Open;
+
+    pub trait Open {
+        fn open() -> Open;
+        fn close(self);
+    }
+}
+
+use handshake::*;
+use session::*;
+
+impl HelloState for Handshake<Hello> {
+    fn start() -> Self {
+        Self { state: Hello }
+    }
+
+    fn established(self) -> Session<Open> {
+        Session { state: Open }
+    }
+}
+
+impl OpenState for Session<Open> {
+    fn open() -> Self {
+        Self { state: Open }
+    }
+
+    fn close(self) {}
+}
+
+fn main() {
+    let session = Handshake::<Hello>::start().established();
+    session.close();
+}
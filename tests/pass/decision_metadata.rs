@@ -0,0 +1,43 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod bulb {
+    #[automaton]
+    pub struct Bulb;
+
+    #[state]
+    pub struct Off;
+
+    #[state]
+    pub struct On;
+
+    pub trait Off {
+        fn screw() -> Off;
+        fn unscrew(self);
+        #[metadata(color = "orange")]
+        fn turn_on(self) -> Switch;
+    }
+
+    pub trait On {
+        fn turn_off(self) -> Off;
+    }
+
+    pub enum Switch {
+        #[metadata(
+            label = "bulb turned on",
+            note = "the bulb works",
+            priority = 1,
+            guard = "filament intact"
+        )]
+        On,
+        #[metadata(
+            label = "bulb failed",
+            note = "the bulb is broken\nreplace it",
+            priority = 0,
+            color = "red"
+        )]
+        Off,
+    }
+}
+
+fn main() {}
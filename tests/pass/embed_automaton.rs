@@ -0,0 +1,35 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(embed_automaton)]
+mod m {
+    #[automaton]
+    pub struct M;
+
+    #[state]
+    pub struct A;
+
+    #[state]
+    pub struct B;
+
+    pub trait A {
+        fn start() -> A;
+        fn next(self) -> B;
+    }
+
+    pub trait B {
+        fn end(self);
+    }
+}
+
+fn main() {
+    assert_eq!(
+        m::AUTOMATON,
+        concat!(
+            r#"{"states":["A","B"],"choices":[],"transitions":["#,
+            r#"{"source":"A","transition":"next","destination":"B"},"#,
+            r#"{"source":"B","transition":"end","destination":null},"#,
+            r#"{"source":null,"transition":"start","destination":"A"}],"#,
+            r#""stats":{"states":2,"choices":0,"transitions":3}}"#
+        )
+    );
+}
@@ -0,0 +1,50 @@
+use typestate_proc_macro::typestate;
+
+#[derive(Debug, PartialEq)]
+pub struct ConstructionError;
+
+#[typestate]
+mod drone {
+    use super::ConstructionError;
+
+    #[automaton]
+    pub struct Drone {
+        pub location: u32,
+    }
+
+    #[state]
+    pub struct Grounded;
+
+    pub trait Grounded {
+        fn new(location: u32) -> Result<Grounded, ConstructionError>;
+        fn stop(self);
+    }
+}
+
+use drone::*;
+
+impl GroundedState for Drone<Grounded> {
+    fn new(location: u32) -> Result<Drone<Grounded>, ConstructionError> {
+        if location <= 100 {
+            Ok(Drone {
+                location,
+                state: Grounded,
+            })
+        } else {
+            Err(ConstructionError)
+        }
+    }
+
+    fn stop(self) {}
+}
+
+fn main() {
+    match Drone::<Grounded>::new(10) {
+        Ok(drone) => drone.stop(),
+        Err(_) => panic!("the location is within bounds"),
+    }
+    assert!(matches!(
+        Drone::<Grounded>::new(1000),
+        Err(ConstructionError)
+    ));
+}
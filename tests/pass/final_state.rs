@@ -0,0 +1,32 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(embed_automaton)]
+mod m {
+    #[automaton]
+    pub struct M;
+
+    #[state]
+    pub struct A;
+
+    #[state]
+    #[final_state]
+    pub struct B;
+
+    pub trait A {
+        fn start() -> A;
+        fn next(self) -> B;
+    }
+}
+
+fn main() {
+    assert_eq!(
+        m::AUTOMATON,
+        concat!(
+            r#"{"states":["A","B"],"choices":[],"transitions":["#,
+            r#"{"source":"A","transition":"next","destination":"B"},"#,
+            r#"{"source":"B","transition":null,"destination":null},"#,
+            r#"{"source":null,"transition":"start","destination":"A"}],"#,
+            r#""stats":{"states":2,"choices":0,"transitions":2,"final_states":1}}"#
+        )
+    );
+}
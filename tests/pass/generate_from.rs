@@ -0,0 +1,121 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(generate_from)]
+mod drone {
+    #[automaton]
+    pub struct Drone {
+        pub altitude: u64,
+        pub battery: u8,
+    }
+
+    #[state]
+    pub struct Idle;
+
+    #[state]
+    pub struct Flying;
+
+    #[state]
+    pub struct Landed;
+
+    pub trait Idle {
+        fn new() -> Idle;
+        fn take_off(self) -> Flying;
+        fn shutdown(self);
+    }
+
+    pub trait Flying {
+        fn land(self) -> Idle;
+        fn hover(self) -> Flying;
+        // transitions taking arguments, guarded or sharing their states have no `From` implementation
+        fn descend(self, altitude: u64) -> Landed;
+    }
+
+    pub trait Landed {
+        #[transition(guard = "super::charged")]
+        fn restart(self) -> Flying;
+        fn park(self) -> Idle;
+        fn refuel(self) -> Idle;
+    }
+}
+
+use drone::*;
+
+fn charged(drone: &Drone<Landed>) -> bool {
+    drone.battery > 10
+}
+
+impl IdleState for Drone<Idle> {
+    fn new() -> Self {
+        Self {
+            altitude: 0,
+            battery: 100,
+            state: Idle,
+        }
+    }
+
+    fn take_off(self) -> Drone<Flying> {
+        Drone {
+            altitude: 100,
+            battery: self.battery - 10,
+            state: Flying,
+        }
+    }
+
+    fn shutdown(self) {}
+}
+
+impl FlyingState for Drone<Flying> {
+    fn land(self) -> Drone<Idle> {
+        Drone {
+            altitude: 0,
+            battery: self.battery,
+            state: Idle,
+        }
+    }
+
+    fn hover(self) -> Drone<Flying> {
+        self
+    }
+
+    fn descend(self, altitude: u64) -> Drone<Landed> {
+        Drone {
+            altitude,
+            battery: self.battery,
+            state: Landed,
+        }
+    }
+}
+
+impl LandedState for Drone<Landed> {
+    fn restart(self) -> Drone<Flying> {
+        Drone {
+            altitude: 100,
+            battery: self.battery,
+            state: Flying,
+        }
+    }
+
+    fn park(self) -> Drone<Idle> {
+        Drone {
+            altitude: 0,
+            battery: self.battery,
+            state: Idle,
+        }
+    }
+
+    fn refuel(self) -> Drone<Idle> {
+        Drone {
+            altitude: 0,
+            battery: 100,
+            state: Idle,
+        }
+    }
+}
+
+fn main() {
+    // the conversion runs the transition
+    let flying: Drone<Flying> = Drone::<Idle>::new().into();
+    assert_eq!((flying.altitude, flying.battery), (100, 90));
+    let idle: Drone<Idle> = flying.into();
+    assert_eq!((idle.altitude, idle.battery), (0, 90));
+}
@@ -0,0 +1,98 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod drone {
+    #[automaton]
+    pub struct Drone {
+        pub battery: u8,
+        pub altitude: u32,
+    }
+
+    #[state]
+    pub struct Idle;
+
+    #[state]
+    pub struct Flying;
+
+    pub trait Idle {
+        fn start(battery: u8) -> Idle;
+        #[transition(guard = "super::battery_ok")]
+        fn take_off<T: Into<u32>>(self, altitude: T) -> Flying;
+        fn stop(self);
+    }
+
+    pub trait Flying {
+        #[transition(guard = "super::low_enough")]
+        fn r#move<F>(self, f: F) -> Flying
+        where
+            F: Fn(u32) -> u32;
+        fn land(self) -> Idle;
+    }
+}
+
+use drone::*;
+
+fn battery_ok(drone: &Drone<Idle>) -> bool {
+    drone.battery > 20
+}
+
+fn low_enough(drone: &Drone<Flying>) -> bool {
+    drone.altitude < 1000
+}
+
+impl IdleState for Drone<Idle> {
+    fn start(battery: u8) -> Drone<Idle> {
+        Drone {
+            battery,
+            altitude: 0,
+            state: Idle,
+        }
+    }
+
+    fn take_off<T: Into<u32>>(self, altitude: T) -> Drone<Flying> {
+        Drone {
+            battery: self.battery,
+            altitude: altitude.into(),
+            state: Flying,
+        }
+    }
+
+    fn stop(self) {}
+}
+
+impl FlyingState for Drone<Flying> {
+    fn r#move<F>(self, f: F) -> Drone<Flying>
+    where
+        F: Fn(u32) -> u32,
+    {
+        Drone {
+            battery: self.battery,
+            altitude: f(self.altitude),
+            state: Flying,
+        }
+    }
+
+    fn land(self) -> Drone<Idle> {
+        Drone {
+            battery: self.battery,
+            altitude: 0,
+            state: Idle,
+        }
+    }
+}
+
+fn main() {
+    let flying = match Drone::<Idle>::start(80).try_take_off(10u8) {
+        Ok(flying) => flying,
+        Err(_) => panic!("the guard should hold"),
+    };
+    assert_eq!(flying.altitude, 10);
+    let flying = match flying.try_move(|altitude| altitude * 200) {
+        Ok(flying) => flying,
+        Err(_) => panic!("the guard should hold"),
+    };
+    match flying.try_move(|altitude| altitude + 1) {
+        Ok(_) => panic!("the guard should not hold"),
+        Err(flying) => flying.land().stop(),
+    }
+}
@@ -0,0 +1,71 @@
+use typestate_proc_macro::typestate;
+
+#[typestate]
+mod drone {
+    #[automaton]
+    pub struct Drone {
+        pub battery: u8,
+    }
+
+    #[state]
+    pub struct Idle;
+
+    #[state]
+    pub struct Flying;
+
+    pub trait Idle {
+        fn start(battery: u8) -> Idle;
+        #[transition(guard = "super::battery_ok")]
+        fn take_off(self, altitude: u32) -> Flying;
+        fn stop(self);
+    }
+
+    pub trait Flying {
+        fn land(self) -> Idle;
+    }
+}
+
+use drone::*;
+
+fn battery_ok(drone: &Drone<Idle>) -> bool {
+    drone.battery > 20
+}
+
+impl IdleState for Drone<Idle> {
+    fn start(battery: u8) -> Drone<Idle> {
+        Drone {
+            battery,
+            state: Idle,
+        }
+    }
+
+    fn take_off(self, _altitude: u32) -> Drone<Flying> {
+        Drone {
+            battery: self.battery,
+            state: Flying,
+        }
+    }
+
+    fn stop(self) {}
+}
+
+impl FlyingState for Drone<Flying> {
+    fn land(self) -> Drone<Idle> {
+        Drone {
+            battery: self.battery,
+            state: Idle,
+        }
+    }
+}
+
+fn main() {
+    match Drone::<Idle>::start(80).try_take_off(10) {
+        Ok(flying) => flying.land().stop(),
+        Err(_) => panic!("the guard should hold"),
+    }
+
+    match Drone::<Idle>::start(10).try_take_off(10) {
+        Ok(_) => panic!("the guard should not hold"),
+        Err(idle) => idle.stop(),
+    }
+}
@@ -0,0 +1,52 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(generate_transition_enum)]
+mod drone {
+    #[automaton]
+    pub struct Drone;
+
+    #[state]
+    pub struct Grounded;
+
+    #[state]
+    pub struct Flying;
+
+    pub trait Grounded {
+        fn start() -> Grounded;
+        fn take_off(self) -> Flying;
+        fn stop(self);
+    }
+
+    pub trait Flying {
+        fn land(self) -> Grounded;
+        #[transition(ignore)]
+        fn crash(self) -> Grounded;
+    }
+}
+
+use drone::*;
+
+impl GroundedState for Drone<Grounded> {
+    fn start() -> Drone<Grounded> {
+        Drone { state: Grounded }
+    }
+
+    fn take_off(self) -> Drone<Flying> {
+        Drone { state: Flying }
+    }
+
+    fn stop(self) {}
+}
+
+// `crash` is not part of the trait, so it does not need to be implemented
+impl FlyingState for Drone<Flying> {
+    fn land(self) -> Drone<Grounded> {
+        Drone { state: Grounded }
+    }
+}
+
+fn main() {
+    let flying = Drone::<Grounded>::start().take_off();
+    assert_eq!(flying.transitions(), &[FlyingTransition::Land]);
+    flying.land().stop();
+}
@@ -0,0 +1,40 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(initial_constructors)]
+mod drone {
+    #[automaton]
+    pub struct Drone {
+        pub x: f32,
+        pub y: f32,
+    }
+
+    #[state]
+    pub struct Grounded;
+
+    #[state]
+    pub struct Charging {
+        pub level: u8,
+    }
+
+    pub trait Grounded {
+        fn start() -> Grounded;
+        fn charge(self) -> Charging;
+        fn shutdown(self);
+    }
+
+    pub trait Charging {
+        fn plug() -> Charging;
+        fn unplug(self) -> Grounded;
+    }
+}
+
+use drone::*;
+
+fn main() {
+    let grounded = Drone::<Grounded>::new(1.0, 2.0);
+    assert_eq!((grounded.x, grounded.y), (1.0, 2.0));
+
+    let charging = Drone::<Charging>::new(3.0, 4.0, Charging { level: 50 });
+    assert_eq!((charging.x, charging.y), (3.0, 4.0));
+    assert_eq!(charging.state.level, 50);
+}
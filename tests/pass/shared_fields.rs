@@ -0,0 +1,31 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(shared_fields, embed_automaton)]
+mod drone {
+    #[automaton]
+    pub struct Drone {
+        pub x: f32,
+        pub y: f32,
+    }
+
+    #[state]
+    pub struct Grounded;
+
+    #[state]
+    pub struct Flying;
+
+    pub trait Grounded {
+        fn start() -> Grounded;
+        fn take_off(self) -> Flying;
+        fn shutdown(self);
+    }
+
+    pub trait Flying {
+        fn land(self) -> Grounded;
+    }
+}
+
+fn main() {
+    // the automaton fields are listed along with the states and transitions
+    assert!(drone::AUTOMATON.contains(r#""shared_fields":["x: f32","y: f32"]"#));
+}
@@ -0,0 +1,69 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(generate_transition_enum)]
+mod drone {
+    #[automaton]
+    pub struct Drone;
+
+    #[state]
+    pub struct Grounded;
+
+    #[state]
+    pub struct Flying;
+
+    pub trait Grounded {
+        fn start() -> Grounded;
+        fn take_off(self) -> Flying;
+        fn stop(self);
+    }
+
+    pub trait Flying {
+        fn r#move(self) -> Flying;
+        fn land(self) -> Grounded;
+    }
+}
+
+use drone::*;
+
+impl GroundedState for Drone<Grounded> {
+    fn start() -> Drone<Grounded> {
+        Drone { state: Grounded }
+    }
+
+    fn take_off(self) -> Drone<Flying> {
+        Drone { state: Flying }
+    }
+
+    fn stop(self) {}
+}
+
+impl FlyingState for Drone<Flying> {
+    fn r#move(self) -> Drone<Flying> {
+        self
+    }
+
+    fn land(self) -> Drone<Grounded> {
+        Drone { state: Grounded }
+    }
+}
+
+fn main() {
+    let drone = Drone::<Grounded>::start();
+    assert_eq!(
+        drone.transitions(),
+        &[GroundedTransition::Stop, GroundedTransition::TakeOff]
+    );
+    for transition in drone.transitions() {
+        match transition {
+            GroundedTransition::Stop => {}
+            GroundedTransition::TakeOff => {}
+        }
+    }
+
+    let drone = drone.take_off();
+    assert_eq!(
+        drone.transitions(),
+        &[FlyingTransition::Land, FlyingTransition::Move]
+    );
+    drone.r#move().land().stop();
+}
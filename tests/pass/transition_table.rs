@@ -0,0 +1,41 @@
+use typestate_proc_macro::typestate;
+
+#[typestate(transition_table)]
+mod light {
+    #[automaton]
+    pub struct Light;
+
+    #[state]
+    pub struct Red;
+
+    #[state]
+    pub struct Green;
+
+    pub trait Red {
+        fn turn_on() -> Red;
+        fn to_green(self) -> Green;
+        fn turn_off(self);
+    }
+
+    pub trait Green {
+        fn to_either(self) -> Either;
+    }
+
+    pub enum Either {
+        Red,
+        Green,
+    }
+}
+
+use light::*;
+
+fn main() {
+    assert_eq!(
+        transition_table(),
+        &[
+            (StateId::Green, "to_either", StateId::Green),
+            (StateId::Green, "to_either", StateId::Red),
+            (StateId::Red, "to_green", StateId::Green),
+        ]
+    );
+}
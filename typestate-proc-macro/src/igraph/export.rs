@@ -1,8 +1,36 @@
 // TODO: document module
 
-/// Type alias for `()` or [`std::error::Error`].
+/// Type alias for `()` or [`RenderError`].
 #[allow(dead_code)]
-type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+type Result = std::result::Result<(), RenderError>;
+
+/// Errors raised while exporting an automaton.
+#[derive(Debug)]
+pub enum RenderError {
+    /// Writing to the output stream failed.
+    Io(std::io::Error),
+    /// The automaton contains a transition which cannot be rendered (e.g. `None -> Decision`).
+    InvalidTransition(&'static str),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::Io(err) => write!(f, "failed to write the automaton: {}", err),
+            RenderError::InvalidTransition(transition) => {
+                write!(f, "invalid transition: {}", transition)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<std::io::Error> for RenderError {
+    fn from(err: std::io::Error) -> Self {
+        RenderError::Io(err)
+    }
+}
 
 /// Blanket trait for [`Export`] implementations.
 pub trait Format {}
@@ -10,51 +38,143 @@ pub trait Format {}
 /// Used to declare output formats for the [`IntermediateAutomaton`].
 pub trait Export<F: Format> {
     /// Export the implementing type as format `F` to the output stream `w`.
-    // TODO: this can be &self
-    fn export<W: std::io::Write>(&self, w: &mut W, _: F) -> Result;
+    fn export<W: std::io::Write>(&self, w: &mut W, _: &F) -> Result;
+}
+
+/// Read the environment variable `name`, if it is set and valid unicode.
+#[cfg_attr(
+    not(any(
+        feature = "mermaid",
+        feature = "html",
+        feature = "plantuml",
+        feature = "dot"
+    )),
+    allow(dead_code)
+)]
+fn env_var(name: &str) -> Option<String> {
+    ::std::env::var_os(name).and_then(|s| s.into_string().ok())
 }
 
 /// The Mermaid format module, containing the marker type and implementation for the respective export trait.
-#[cfg(feature = "mermaid")]
+#[cfg(any(feature = "mermaid", feature = "html"))]
 pub mod mermaid {
-    use super::{Export, Result};
+    use super::{Export, RenderError, Result};
     use crate::igraph::{IntermediateGraph, Node, Transition};
     use std::{
         fmt::{Debug, Display},
         hash::Hash,
     };
 
-    /// The mermaid format struct.
-    #[derive(Clone, Copy)]
-    pub struct Mermaid;
+    /// The mermaid format struct, along with its settings.
+    #[derive(Clone, Debug, Default)]
+    pub struct Mermaid {
+        /// The diagram direction (e.g. `LR`), by default top to bottom.
+        pub direction: Option<String>,
+        /// The style applied to the accepting states, if any.
+        pub accepting_style: Option<String>,
+    }
+
+    impl Mermaid {
+        /// Read the settings from the `MERMAID_*` environment variables.
+        pub fn from_env() -> Self {
+            Self {
+                direction: super::env_var("MERMAID_DIRECTION"),
+                accepting_style: super::env_var("MERMAID_ACCEPTING_STYLE"),
+            }
+        }
+    }
 
     /// Blanket implementation for the [`Mermaid`] format.
     impl super::Format for Mermaid {}
 
+    /// Replace the characters Mermaid would interpret with their entity codes.
+    fn escape<D: Display>(text: D) -> String {
+        text.to_string()
+            .chars()
+            .map(|c| match c {
+                '#' => "#35;".to_string(),
+                ';' => "#59;".to_string(),
+                '"' => "#34;".to_string(),
+                '<' => "#60;".to_string(),
+                '>' => "#62;".to_string(),
+                '\n' => " ".to_string(),
+                c => c.to_string(),
+            })
+            .collect()
+    }
+
     impl<S, T> Export<Mermaid> for IntermediateGraph<S, T>
     where
         S: Hash + Eq + Debug + Clone + Display,
         T: Hash + Eq + Debug + Clone + Display,
     {
-        fn export<W: std::io::Write>(&self, w: &mut W, f: Mermaid) -> Result {
+        fn export<W: std::io::Write>(&self, w: &mut W, f: &Mermaid) -> Result {
             writeln!(w, "stateDiagram-v2")?;
 
-            if let Some(v) = self.delta.get(&None) {
-                for (t, dst) in v {
-                    (t, dst).export(w, f)?
-                }
+            if let Some(direction) = &f.direction {
+                writeln!(w, "direction {}", direction)?;
+            }
+
+            // some renderers reject diagrams without states, so empty automata get a placeholder
+            if self.states.is_empty() && self.choices.is_empty() {
+                writeln!(w, "%% empty automaton")?;
+                writeln!(w, "state \"(empty)\" as _empty_")?;
+            }
+
+            // follow the breadth-first order so the output does not depend on the hashing order
+            let order = self.bfs_order();
+
+            for (t, dst) in self.transitions_from(&None) {
+                (t, dst).export(w, f)?
             }
 
-            for choice in &self.choices {
+            for choice in order.iter().filter(|s| self.choices.contains(s)) {
                 writeln!(w, "state {} <<choice>>", choice)?;
             }
 
-            for (src, v) in &self.delta {
-                if let Some(src) = src {
-                    for (t, dst) in v {
-                        (src, t, dst).export(w, f)?
-                    }
+            for src in &order {
+                for (t, dst) in self.transitions_from(&Some((*src).clone())) {
+                    (*src, t, dst).export(w, f)?
+                }
+            }
+
+            for state in order.iter().filter(|s| self.final_states.contains(s)) {
+                writeln!(w, "{} --> [*]", state)?;
+            }
+
+            // style the accepting states, i.e. the ones with a transition into the final state
+            if let Some(style) = &f.accepting_style {
+                let accepting: Vec<_> = order
+                    .iter()
+                    .filter(|s| {
+                        self.final_states.contains(s)
+                            || self.transitions_from(&Some((**s).clone())).iter().any(
+                                |(_, dst)| matches!(dst, Node::State(state) if state.state.is_none()),
+                            )
+                    })
+                    .map(|s| s.to_string())
+                    .collect();
+                if !accepting.is_empty() {
+                    writeln!(w, "classDef accepting {}", style)?;
+                    writeln!(w, "class {} accepting", accepting.join(","))?;
+                }
+            }
+
+            // Mermaid does not support floating notes, so the note is attached to an initial state
+            let initial_state = self.delta.get(&None).and_then(|v| {
+                v.values()
+                    .filter_map(|dst| match dst {
+                        Node::State(state) => state.state.as_ref(),
+                        Node::Decision(_) => None,
+                    })
+                    .min_by_key(|state| state.to_string())
+            });
+            if let (Some(state), false) = (initial_state, self.shared_fields.is_empty()) {
+                writeln!(w, "note left of {}", state)?;
+                for field in &self.shared_fields {
+                    writeln!(w, "    {}", escape(field))?;
                 }
+                writeln!(w, "end note")?;
             }
 
             Ok(())
@@ -66,24 +186,20 @@ pub mod mermaid {
         S: Hash + Eq + Debug + Clone + Display,
         T: Hash + Eq + Debug + Clone + Display,
     {
-        fn export<W: std::io::Write>(&self, w: &mut W, _: Mermaid) -> Result {
-            let t = &self.0.transition;
+        fn export<W: std::io::Write>(&self, w: &mut W, _: &Mermaid) -> Result {
+            let t = self.0;
             let dst = self.1;
             match dst {
                 Node::State(state) => match &state.state {
-                    None => unreachable!("invalid transition: None -> None"),
+                    None => return Err(RenderError::InvalidTransition("None -> None")),
                     Some(s) => {
-                        // if there is a transition label, use that instead of the existing label
-                        if let Some(label) = &state.metadata.transition_label {
-                            writeln!(w, "[*] --> {} : {}", label, t)?
-                        } else {
-                            writeln!(w, "[*] --> {} : {}", s, t)?
-                        }
+                        // if there is a transition label, use that instead of the transition name
+                        writeln!(w, "[*] --> {} : {}", s, escape(state.label_or(t)))?
                     }
                 },
                 Node::Decision(_) => {
                     // NOTE: unsure about this
-                    unreachable!("invalid transition: None -> Decision")
+                    return Err(RenderError::InvalidTransition("None -> Decision"));
                 }
             }
             Ok(())
@@ -95,33 +211,29 @@ pub mod mermaid {
         S: Hash + Eq + Debug + Clone + Display,
         T: Hash + Eq + Debug + Clone + Display,
     {
-        fn export<W: std::io::Write>(&self, w: &mut W, _: Mermaid) -> Result {
+        fn export<W: std::io::Write>(&self, w: &mut W, _: &Mermaid) -> Result {
             let src = self.0;
-            let t = &self.1.transition;
+            let t = self.1;
             let dst = self.2;
 
             match dst {
                 Node::State(state) => match &state.state {
-                    None => writeln!(w, "{} --> [*] : {}", src, t)?,
+                    None => writeln!(w, "{} --> [*] : {}", src, escape(state.label_or(t)))?,
                     Some(s) => {
-                        // if there is a transition label, use that instead of the existing label
-                        if let Some(label) = &state.metadata.transition_label {
-                            writeln!(w, "{} --> {} : {}", src, label, t)?
-                        } else {
-                            writeln!(w, "{} --> {} : {}", src, s, t)?
-                        }
+                        // if there is a transition label, use that instead of the transition name
+                        writeln!(w, "{} --> {} : {}", src, s, escape(state.label_or(t)))?
                     }
                 },
                 Node::Decision(decision) => {
                     for s in decision {
                         if let Some(state) = &s.state {
-                            if let Some(label) = &s.metadata.transition_label {
-                                writeln!(w, "{} --> {} : {}", src, state, label)?
+                            if let Some(label) = s.branch_label() {
+                                writeln!(w, "{} --> {} : {}", src, state, escape(label))?
                             } else {
                                 writeln!(w, "{} --> {}", src, state)?
                             }
-                        } else if let Some(label) = &s.metadata.transition_label {
-                            writeln!(w, "{} --> [*] : {}", src, label)?
+                        } else if let Some(label) = s.branch_label() {
+                            writeln!(w, "{} --> [*] : {}", src, escape(label))?
                         } else {
                             writeln!(w, "{} --> [*]", src)?
                         }
@@ -134,92 +246,404 @@ pub mod mermaid {
     }
 }
 
+/// The HTML format module, containing the marker type and implementation for the respective export trait.
+///
+/// The page wraps the Mermaid diagram and loads Mermaid.js to render it.
+#[cfg(feature = "html")]
+pub mod html {
+    use super::{mermaid::Mermaid, Export, Result};
+    use crate::igraph::IntermediateGraph;
+    use std::{
+        fmt::{Debug, Display},
+        hash::Hash,
+    };
+
+    /// Mermaid.js location used when `HTML_MERMAID_SRC` is not set.
+    const MERMAID_SRC: &str = "https://cdn.jsdelivr.net/npm/mermaid/dist/mermaid.min.js";
+
+    /// The HTML format struct, along with its settings.
+    #[derive(Clone, Debug)]
+    pub struct Html {
+        /// The settings of the embedded Mermaid diagram.
+        pub mermaid: Mermaid,
+        /// The location Mermaid.js is loaded from.
+        pub mermaid_src: String,
+    }
+
+    impl Html {
+        /// Read the settings from the `HTML_*` and `MERMAID_*` environment variables.
+        pub fn from_env() -> Self {
+            Self {
+                mermaid: Mermaid::from_env(),
+                mermaid_src: super::env_var("HTML_MERMAID_SRC")
+                    .unwrap_or_else(|| MERMAID_SRC.to_string()),
+            }
+        }
+    }
+
+    impl Default for Html {
+        fn default() -> Self {
+            Self {
+                mermaid: Mermaid::default(),
+                mermaid_src: MERMAID_SRC.to_string(),
+            }
+        }
+    }
+
+    /// Blanket implementation for the [`Html`] format.
+    impl super::Format for Html {}
+
+    /// Escape the characters the HTML parser would interpret.
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    impl<S, T> Export<Html> for IntermediateGraph<S, T>
+    where
+        S: Hash + Eq + Debug + Clone + Display,
+        T: Hash + Eq + Debug + Clone + Display,
+    {
+        fn export<W: std::io::Write>(&self, w: &mut W, f: &Html) -> Result {
+            let mut diagram = Vec::<u8>::new();
+            self.export(&mut diagram, &f.mermaid)?;
+            let diagram = String::from_utf8_lossy(&diagram);
+
+            // a local copy of Mermaid.js can be used to view the page offline
+            let src = &f.mermaid_src;
+
+            writeln!(w, "<!DOCTYPE html>")?;
+            writeln!(w, "<html>")?;
+            writeln!(w, "<head>")?;
+            writeln!(w, "  <meta charset=\"utf-8\">")?;
+            writeln!(w, "  <script src=\"{}\"></script>", escape(src))?;
+            writeln!(
+                w,
+                "  <script>mermaid.initialize({{ startOnLoad: true }});</script>"
+            )?;
+            writeln!(w, "</head>")?;
+            writeln!(w, "<body>")?;
+            writeln!(w, "<div class=\"mermaid\">")?;
+            write!(w, "{}", escape(&diagram))?;
+            writeln!(w, "</div>")?;
+            writeln!(w, "</body>")?;
+            writeln!(w, "</html>")?;
+
+            Ok(())
+        }
+    }
+}
+
+/// The JSON format module, containing the marker type and implementation for the respective export trait.
+pub mod json {
+    use super::{Export, Result};
+    use crate::igraph::{IntermediateGraph, Node};
+    use std::{
+        fmt::{Debug, Display},
+        hash::Hash,
+    };
+
+    /// The JSON format struct.
+    #[derive(Clone, Copy)]
+    pub struct Json;
+
+    /// Blanket implementation for the [`Json`] format.
+    impl super::Format for Json {}
+
+    /// Write `value` as a JSON string.
+    fn string<D: Display>(value: D) -> String {
+        let mut res = String::from("\"");
+        for c in value.to_string().chars() {
+            match c {
+                '"' => res.push_str("\\\""),
+                '\\' => res.push_str("\\\\"),
+                '\n' => res.push_str("\\n"),
+                c if c.is_control() => res.push_str(&format!("\\u{:04x}", c as u32)),
+                c => res.push(c),
+            }
+        }
+        res.push('"');
+        res
+    }
+
+    /// Write `value` as a JSON string or `null`.
+    fn option<D: Display>(value: Option<D>) -> String {
+        value.map_or_else(|| "null".to_string(), string)
+    }
+
+    impl<S, T> Export<Json> for IntermediateGraph<S, T>
+    where
+        S: Hash + Eq + Debug + Clone + Display,
+        T: Hash + Eq + Debug + Clone + Display,
+    {
+        fn export<W: std::io::Write>(&self, w: &mut W, _: &Json) -> Result {
+            // sort everything so the output does not depend on the hashing order
+            let mut states: Vec<_> = self.states.iter().map(string).collect();
+            states.sort();
+            let mut choices: Vec<_> = self.choices.iter().map(string).collect();
+            choices.sort();
+
+            let mut transitions: Vec<_> = self
+                .delta
+                .iter()
+                .flat_map(|(src, v)| {
+                    v.iter().map(move |(t, dst)| {
+                        let destination = match dst {
+                            Node::State(state) => option(state.state.as_ref()),
+                            Node::Decision(decision) => format!(
+                                "[{}]",
+                                decision
+                                    .iter()
+                                    .map(|s| option(s.state.as_ref()))
+                                    .collect::<Vec<_>>()
+                                    .join(",")
+                            ),
+                        };
+                        // the weight is only written when present, keeping the output of unweighted automata unchanged
+                        let weight = t
+                            .weight
+                            .filter(|weight| weight.is_finite())
+                            .map_or_else(String::new, |weight| format!(",\"weight\":{}", weight));
+                        format!(
+                            "{{\"source\":{},\"transition\":{},\"destination\":{}{}}}",
+                            option(src.as_ref()),
+                            string(&t.transition),
+                            destination,
+                            weight
+                        )
+                    })
+                })
+                .chain(self.final_states.iter().map(|state| {
+                    format!(
+                        "{{\"source\":{},\"transition\":null,\"destination\":null}}",
+                        string(state)
+                    )
+                }))
+                .collect();
+            transitions.sort();
+
+            // the shared fields are only written when present, as with the weight
+            let shared_fields = if self.shared_fields.is_empty() {
+                String::new()
+            } else {
+                let fields: Vec<_> = self.shared_fields.iter().map(string).collect();
+                format!(",\"shared_fields\":[{}]", fields.join(","))
+            };
+
+            // the `#[final_state]` entries of the transitions are counted apart, only when present
+            let final_states = if self.final_states.is_empty() {
+                String::new()
+            } else {
+                format!(",\"final_states\":{}", self.final_states.len())
+            };
+
+            write!(
+                w,
+                "{{\"states\":[{}],\"choices\":[{}],\"transitions\":[{}]{},\"stats\":{{\"states\":{},\"choices\":{},\"transitions\":{}{}}}}}",
+                states.join(","),
+                choices.join(","),
+                transitions.join(","),
+                shared_fields,
+                self.state_count(),
+                self.choice_count(),
+                self.transition_count(),
+                final_states
+            )?;
+
+            Ok(())
+        }
+    }
+}
+
 /// The PlantUML format module, containing the marker type and implementation for the respective export trait.
 // #[cfg(feature = "plantuml")]
+// the module is always built, only the settings are unused without the feature
+#[cfg_attr(not(feature = "plantuml"), allow(dead_code))]
 pub mod plantuml {
-    use super::{Export, Result};
-    use crate::igraph::{IntermediateGraph, Node, Transition};
+    use super::{Export, RenderError, Result};
+    use crate::igraph::{IntermediateGraph, Node, StateNode, Transition};
     use std::{
         fmt::{Debug, Display},
         hash::Hash,
     };
 
-    #[derive(Clone, Copy)]
-    pub struct PlantUml;
+    /// The PlantUML format struct, along with its settings.
+    #[derive(Clone, Debug, Default)]
+    pub struct PlantUml {
+        /// The minimum space between two adjacent nodes in the same rank.
+        pub nodesep: Option<String>,
+        /// The desired rank separation.
+        pub ranksep: Option<String>,
+        /// The preset `skinparam` theme, if any.
+        pub theme: Option<Theme>,
+    }
+
+    impl PlantUml {
+        /// Read the settings from the `PLANTUML_*` environment variables.
+        pub fn from_env() -> Self {
+            // non-unicode values fall back to PlantUML's default separation
+            let sep = |name| {
+                ::std::env::var_os(name)
+                    .map(|s| s.into_string().unwrap_or_else(|_| "30".to_string()))
+            };
+            Self {
+                nodesep: sep("PLANTUML_NODESEP"),
+                ranksep: sep("PLANTUML_RANKSEP"),
+                theme: super::env_var("PLANTUML_THEME").and_then(|s| Theme::from_name(&s)),
+            }
+        }
+    }
+
+    /// Return the transition arrow, colored if a `color` is given.
+    fn arrow(color: Option<&str>) -> String {
+        color.map_or_else(
+            || "-->".to_string(),
+            |color| format!("-[#{}]->", color.trim_start_matches('#')),
+        )
+    }
 
     impl super::Format for PlantUml {}
 
+    /// Preset `skinparam` blocks, selected with `PLANTUML_THEME`.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum Theme {
+        Dark,
+        Minimal,
+    }
+
+    impl Theme {
+        /// Return the theme named `name` (case insensitive), if any.
+        fn from_name(name: &str) -> Option<Self> {
+            match name.to_lowercase().as_str() {
+                "dark" => Some(Self::Dark),
+                "minimal" => Some(Self::Minimal),
+                _ => None,
+            }
+        }
+
+        fn skinparams(self) -> &'static [&'static str] {
+            match self {
+                Self::Dark => &[
+                    "skinparam backgroundColor #2b2b2b",
+                    "skinparam defaultFontColor #e0e0e0",
+                    "skinparam ArrowColor #a0a0a0",
+                    "skinparam StateBackgroundColor #3c3f41",
+                    "skinparam StateBorderColor #a0a0a0",
+                    "skinparam NoteBackgroundColor #4e5254",
+                    "skinparam NoteBorderColor #a0a0a0",
+                ],
+                Self::Minimal => &[
+                    "skinparam monochrome true",
+                    "skinparam shadowing false",
+                    "skinparam StateBackgroundColor white",
+                    "skinparam defaultFontName Helvetica",
+                ],
+            }
+        }
+    }
+
     impl<S, T> Export<PlantUml> for IntermediateGraph<S, T>
     where
         S: Hash + Eq + Debug + Clone + Display,
         T: Hash + Eq + Debug + Clone + Display,
     {
-        fn export<W: std::io::Write>(&self, w: &mut W, f: PlantUml) -> Result {
+        fn export<W: std::io::Write>(&self, w: &mut W, f: &PlantUml) -> Result {
             writeln!(w, "@startuml")?;
             writeln!(w, "hide empty description")?;
 
-            if let Some(s) = ::std::env::var_os("PLANTUML_NODESEP") {
-                w.write_fmt(format_args!(
-                    "skinparam nodesep {}\n",
-                    s.into_string().unwrap_or_else(|_| "30".to_string())
-                ))?;
+            if let Some(theme) = f.theme {
+                for skinparam in theme.skinparams() {
+                    writeln!(w, "{}", skinparam)?;
+                }
             }
 
-            if let Some(s) = ::std::env::var_os("PLANTUML_RANKSEP") {
-                w.write_fmt(format_args!(
-                    "skinparam ranksep {}\n",
-                    s.into_string().unwrap_or_else(|_| "30".to_string())
-                ))?;
+            if let Some(nodesep) = &f.nodesep {
+                writeln!(w, "skinparam nodesep {}", nodesep)?;
             }
 
-            if let Some(v) = self.delta.get(&None) {
-                for (t, dst) in v {
-                    (t, dst).export(w, f)?
-                }
+            if let Some(ranksep) = &f.ranksep {
+                writeln!(w, "skinparam ranksep {}", ranksep)?;
+            }
+
+            // some renderers reject diagrams without states, so empty automata get a placeholder
+            if self.states.is_empty() && self.choices.is_empty() {
+                writeln!(w, "' empty automaton")?;
+                writeln!(w, "state \"(empty)\" as _empty_")?;
             }
 
-            for choice in &self.choices {
+            // follow the breadth-first order so the output does not depend on the hashing order
+            let order = self.bfs_order();
+
+            for (t, dst) in self.transitions_from(&None) {
+                (t, dst).export(w, f)?
+            }
+
+            for choice in order.iter().filter(|s| self.choices.contains(s)) {
                 writeln!(w, "state {} <<choice>>", choice)?;
             }
 
-            for (src, v) in &self.delta {
-                if let Some(src) = src {
-                    for (t, dst) in v {
-                        (src, t, dst).export(w, f)?
-                    }
+            for src in &order {
+                for (t, dst) in self.transitions_from(&Some((*src).clone())) {
+                    (*src, t, dst).export(w, f)?
+                }
+            }
+
+            for state in order.iter().filter(|s| self.final_states.contains(s)) {
+                writeln!(w, "{} --> [*]", state)?;
+            }
+
+            if !self.shared_fields.is_empty() {
+                writeln!(w, "note as SharedFields")?;
+                for field in &self.shared_fields {
+                    writeln!(w, "  {}", field)?;
                 }
+                writeln!(w, "end note")?;
             }
 
-            writeln!(w, "@end")?;
+            writeln!(w, "@enduml")?;
 
             Ok(())
         }
     }
 
+    /// Write the note attached to the transition into `node`, if any, on the last written link.
+    fn note<W: std::io::Write, S>(w: &mut W, node: &StateNode<S>) -> Result {
+        if let Some(note) = &node.metadata.note {
+            writeln!(w, "note on link")?;
+            for line in note.lines() {
+                writeln!(w, "  {}", line)?;
+            }
+            writeln!(w, "end note")?;
+        }
+        Ok(())
+    }
+
     impl<S, T> Export<PlantUml> for (&Transition<T>, &Node<S>)
     where
         S: Hash + Eq + Debug + Clone + Display,
         T: Hash + Eq + Debug + Clone + Display,
     {
-        fn export<W: std::io::Write>(&self, w: &mut W, _: PlantUml) -> Result {
-            let t = &self.0.transition;
+        fn export<W: std::io::Write>(&self, w: &mut W, _: &PlantUml) -> Result {
+            let t = self.0;
             let dst = self.1;
+            let arrow = arrow(t.color());
 
             match dst {
-                Node::State(state) => match &state.state {
-                    None => unreachable!("invalid transition: None -> None"),
-                    Some(s) => {
-                        // if there is a transition label, use that instead of the existing label
-                        if let Some(label) = &state.metadata.transition_label {
-                            writeln!(w, "[*] --> {} : {}", label, t)?
-                        } else {
-                            writeln!(w, "[*] --> {} : {}", s, t)?
+                Node::State(state) => {
+                    match &state.state {
+                        None => return Err(RenderError::InvalidTransition("None -> None")),
+                        Some(s) => {
+                            // if there is a transition label, use that instead of the transition name
+                            writeln!(w, "[*] {} {} : {}", arrow, s, state.label_or(t))?
                         }
                     }
-                },
+                    note(w, state)?
+                }
                 Node::Decision(_) => {
                     // NOTE: unsure about this
-                    unreachable!("invalid transition: None -> Decision")
+                    return Err(RenderError::InvalidTransition("None -> Decision"));
                 }
             }
 
@@ -232,36 +656,39 @@ pub mod plantuml {
         S: Hash + Eq + Debug + Clone + Display,
         T: Hash + Eq + Debug + Clone + Display,
     {
-        fn export<W: std::io::Write>(&self, w: &mut W, _: PlantUml) -> Result {
+        fn export<W: std::io::Write>(&self, w: &mut W, _: &PlantUml) -> Result {
             let src = self.0;
-            let t = &self.1.transition;
+            let t = self.1;
             let dst = self.2;
+            let arrow = arrow(t.color());
 
             match dst {
-                Node::State(state) => match &state.state {
-                    None => writeln!(w, "{} --> [*] : {}", src, t)?,
-                    Some(s) => {
-                        // if there is a transition label, use that instead of the existing label
-                        if let Some(label) = &state.metadata.transition_label {
-                            writeln!(w, "{} --> {} : {}", src, label, t)?
-                        } else {
-                            writeln!(w, "{} --> {} : {}", src, s, t)?
+                Node::State(state) => {
+                    match &state.state {
+                        None => writeln!(w, "{} {} [*] : {}", src, arrow, state.label_or(t))?,
+                        Some(s) => {
+                            // if there is a transition label, use that instead of the transition name
+                            writeln!(w, "{} {} {} : {}", src, arrow, s, state.label_or(t))?
                         }
                     }
-                },
+                    note(w, state)?
+                }
                 Node::Decision(decision) => {
                     for s in decision {
+                        // the branch color takes precedence over the transition color
+                        let arrow = self::arrow(s.color().or_else(|| t.color()));
                         if let Some(state) = &s.state {
-                            if let Some(label) = &s.metadata.transition_label {
-                                writeln!(w, "{} --> {} : {}", src, state, label)?
+                            if let Some(label) = s.branch_label() {
+                                writeln!(w, "{} {} {} : {}", src, arrow, state, label)?
                             } else {
-                                writeln!(w, "{} --> {}", src, state)?
+                                writeln!(w, "{} {} {}", src, arrow, state)?
                             }
-                        } else if let Some(label) = &s.metadata.transition_label {
-                            writeln!(w, "{} --> [*] : {}", src, label)?
+                        } else if let Some(label) = s.branch_label() {
+                            writeln!(w, "{} {} [*] : {}", src, arrow, label)?
                         } else {
-                            writeln!(w, "{} --> [*]", src)?
+                            writeln!(w, "{} {} [*]", src, arrow)?
                         }
+                        note(w, s)?
                     }
                 }
             }
@@ -274,7 +701,7 @@ pub mod plantuml {
 /// The DOT format module, containing the marker type and implementation for the respective export trait.
 #[cfg(feature = "dot")]
 pub mod dot {
-    use super::{Export, Result};
+    use super::{Export, RenderError, Result};
     use crate::igraph::{IntermediateGraph, Node, Transition};
     use std::{
         fmt::{Debug, Display},
@@ -287,38 +714,157 @@ pub mod dot {
             .unwrap_or_else(|| var_default.to_string())
     }
 
-    #[derive(Clone, Copy)]
-    pub struct Dot;
+    /// Where the transition labels are written.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum LabelMode {
+        /// On the edge.
+        Inline,
+        /// As a tooltip, shown on hover in SVG output.
+        Tooltip,
+        /// Both on the edge and as a tooltip.
+        Both,
+    }
+
+    /// The DOT format struct, along with its settings.
+    #[derive(Clone, Debug)]
+    pub struct Dot {
+        pub pad: String,
+        pub nodesep: String,
+        pub ranksep: String,
+        /// Merge multiedges into a single edge.
+        pub concentrate: bool,
+        pub label_mode: LabelMode,
+        /// The direction of the graph layout (e.g. `LR`), if any.
+        pub rankdir: Option<String>,
+        /// Chain the nodes in breadth-first order with invisible edges.
+        pub ordering_edges: bool,
+        /// Place the nodes at the same breadth-first depth on the same rank.
+        pub rank_by_depth: bool,
+        /// Shade the states which cannot be reached from an initial state.
+        pub shade_unreachable: bool,
+    }
+
+    impl Dot {
+        /// Read the settings from the `DOT_*` environment variables.
+        pub fn from_env() -> Self {
+            let default = Self::default();
+            Self {
+                pad: var_or_default("DOT_PAD", &default.pad),
+                nodesep: var_or_default("DOT_NODESEP", &default.nodesep),
+                ranksep: var_or_default("DOT_RANKSEP", &default.ranksep),
+                concentrate: var_or_default("DOT_CONCENTRATE", "false") == "true",
+                label_mode: match var_or_default("DOT_LABEL_MODE", "inline").as_str() {
+                    "tooltip" => LabelMode::Tooltip,
+                    "both" => LabelMode::Both,
+                    _ => LabelMode::Inline,
+                },
+                rankdir: super::env_var("DOT_RANKDIR"),
+                ordering_edges: var_or_default("DOT_ORDERING_EDGES", "false") == "true",
+                rank_by_depth: var_or_default("DOT_RANK_BY_DEPTH", "false") == "true",
+                shade_unreachable: var_or_default("DOT_SHADE_UNREACHABLE", "false") == "true",
+            }
+        }
+    }
+
+    impl Default for Dot {
+        fn default() -> Self {
+            Self {
+                pad: "0.25".to_string(),
+                nodesep: "0.75".to_string(),
+                ranksep: "1".to_string(),
+                concentrate: false,
+                label_mode: LabelMode::Inline,
+                rankdir: None,
+                ordering_edges: false,
+                rank_by_depth: false,
+                shade_unreachable: false,
+            }
+        }
+    }
 
     impl super::Format for Dot {}
 
     const DOT_SPECIAL_NODE: &str =
         r#"label="", fillcolor=black, fixedsize=true, height=0.25, style=filled"#;
 
-    impl<S, T> Export<Dot> for IntermediateAutomaton<S, T>
+    impl<S, T> Export<Dot> for IntermediateGraph<S, T>
     where
         S: Hash + Eq + Debug + Clone + Display,
         T: Hash + Eq + Debug + Clone + Display,
     {
-        fn export<W: std::io::Write>(&self, w: &mut W, f: Dot) -> Result {
+        fn export<W: std::io::Write>(&self, w: &mut W, f: &Dot) -> Result {
             writeln!(w, "digraph Automata {{")?;
 
             w.write_fmt(format_args!(
                 "  graph [pad=\"{}\", nodesep=\"{}\", ranksep=\"{}\"];\n",
-                var_or_default("DOT_PAD", "0.25"),
-                var_or_default("DOT_NODESEP", "0.75"),
-                var_or_default("DOT_RANKSEP", "1"),
+                f.pad, f.nodesep, f.ranksep,
             ))?;
 
+            if f.concentrate {
+                writeln!(w, "  concentrate=true;")?;
+            }
+
+            if let Some(rankdir) = &f.rankdir {
+                writeln!(w, "  rankdir={};", rankdir)?;
+            }
+
             writeln!(w, "  _initial_ [{}, shape=circle];", DOT_SPECIAL_NODE)?;
             writeln!(w, "  _final_ [{}, shape=doublecircle];", DOT_SPECIAL_NODE)?;
 
-            for s in &self.choices {
+            if !self.shared_fields.is_empty() {
+                let label: String = self
+                    .shared_fields
+                    .iter()
+                    .map(|field| format!("{}\\l", escape(field)))
+                    .collect();
+                writeln!(w, "  _shared_ [label=\"{}\", shape=note];", label)?;
+            }
+
+            // follow the breadth-first order so the output does not depend on the hashing order
+            let order = self.bfs_order();
+
+            for s in order.iter().filter(|s| self.choices.contains(s)) {
                 writeln!(w, "  {} [shape=diamond];", s)?
             }
-            for (src, v) in &self.delta {
-                for (t, dst) in v {
-                    (src, t, dst).export(w, f)?
+
+            // shade the states which cannot be reached, the diagram is exported before validation
+            if f.shade_unreachable {
+                let reachable = self.reachable_states();
+                let mut unreachable: Vec<_> = self
+                    .states
+                    .iter()
+                    .filter(|s| !reachable.contains(s))
+                    .collect();
+                unreachable.sort_by_key(|s| s.to_string());
+                for s in unreachable {
+                    writeln!(w, "  {} [style=filled, fillcolor=gray];", s)?
+                }
+            }
+            // group the nodes at the same breadth-first depth, laying the diagram out in layers
+            if f.rank_by_depth {
+                for layer in self.bfs_layers().iter().filter(|layer| layer.len() > 1) {
+                    let nodes: String = layer.iter().map(|s| format!(" {};", s)).collect();
+                    writeln!(w, "  {{ rank=same;{} }}", nodes)?;
+                }
+            }
+            for (t, dst) in self.transitions_from(&None) {
+                (&None, t, dst).export(w, f)?
+            }
+            for src in &order {
+                let src = Some((*src).clone());
+                for (t, dst) in self.transitions_from(&src) {
+                    (&src, t, dst).export(w, f)?
+                }
+            }
+
+            for state in order.iter().filter(|s| self.final_states.contains(s)) {
+                writeln!(w, "  {} -> _final_;", state)?;
+            }
+
+            // chain the nodes in breadth-first order with invisible edges, hinting the layout
+            if f.ordering_edges {
+                for pair in order.windows(2) {
+                    writeln!(w, "  {} -> {} [style=invis];", pair[0], pair[1])?;
                 }
             }
 
@@ -327,41 +873,78 @@ pub mod dot {
         }
     }
 
+    /// Escape the quotes and backslashes in `text`, so it can be written inside a quoted DOT string.
+    fn escape(text: &str) -> String {
+        text.replace('\\', "\\\\").replace('"', "\\\"")
+    }
+
+    /// Format the edge attributes (e.g. ` [label="f", color="red"]`), if any.
+    ///
+    /// Depending on the `mode`, the label is written inline,
+    /// as a tooltip (shown on hover in SVG) or both.
+    fn edge_attributes(label: Option<String>, color: Option<&str>, mode: LabelMode) -> String {
+        let (inline, tooltip) = match mode {
+            LabelMode::Inline => (true, false),
+            LabelMode::Tooltip => (false, true),
+            LabelMode::Both => (true, true),
+        };
+        let attributes: Vec<_> = label
+            .iter()
+            .filter(|_| inline)
+            .map(|label| format!("label=\"{}\"", escape(label)))
+            .chain(
+                label
+                    .iter()
+                    .filter(|_| tooltip)
+                    .map(|label| format!("tooltip=\"{}\"", escape(label))),
+            )
+            .chain(color.map(|color| format!("color=\"{}\"", escape(color))))
+            .collect();
+        if attributes.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", attributes.join(", "))
+        }
+    }
+
     impl<S, T> Export<Dot> for (&Option<S>, &Transition<T>, &Node<S>)
     where
         S: Hash + Eq + Debug + Clone + Display,
         T: Hash + Eq + Debug + Clone + Display,
     {
-        fn export<W: std::io::Write>(&self, w: &mut W, _: Dot) -> Result {
+        fn export<W: std::io::Write>(&self, w: &mut W, f: &Dot) -> Result {
             let src = self.0;
-            let t = &self.1.transition;
+            let t = self.1;
             let dst = self.2;
+            let color = t.color();
 
             if let Some(src) = src {
                 match dst {
                     Node::State(state) => match &state.state {
-                        None => writeln!(w, "  {} -> _final_ [label=\"{}\"];", src, t)?,
+                        None => {
+                            let attributes =
+                                edge_attributes(Some(state.label_or(t)), color, f.label_mode);
+                            writeln!(w, "  {} -> _final_{};", src, attributes)?
+                        }
                         Some(s) => {
-                            // if there is a transition label, use that instead of the existing label
-                            if let Some(label) = &state.metadata.transition_label {
-                                writeln!(w, "  {} -> {} [label=\"{}\"];", src, label, t)?
-                            } else {
-                                writeln!(w, "  {} -> {} [label=\"{}\"];", src, s, t)?
-                            }
+                            // if there is a transition label, use that instead of the transition name
+                            let attributes =
+                                edge_attributes(Some(state.label_or(t)), color, f.label_mode);
+                            writeln!(w, "  {} -> {}{};", src, s, attributes)?
                         }
                     },
                     Node::Decision(decision) => {
                         for s in decision {
+                            // the branch color takes precedence over the transition color
+                            let attributes = edge_attributes(
+                                s.branch_label(),
+                                s.color().or(color),
+                                f.label_mode,
+                            );
                             if let Some(state) = &s.state {
-                                if let Some(label) = &s.metadata.transition_label {
-                                    writeln!(w, "  {} -> {} [label=\"{}\"];", src, state, label)?
-                                } else {
-                                    writeln!(w, "  {} -> {};", src, state)?
-                                }
-                            } else if let Some(label) = &s.metadata.transition_label {
-                                writeln!(w, "  {} -> _final_ [label=\"{}\"];", src, label)?
+                                writeln!(w, "  {} -> {}{};", src, state, attributes)?
                             } else {
-                                writeln!(w, "  {} -> _final_;", src)?
+                                writeln!(w, "  {} -> _final_{};", src, attributes)?
                             }
                         }
                     }
@@ -369,19 +952,17 @@ pub mod dot {
             } else {
                 match dst {
                     Node::State(state) => match &state.state {
-                        None => unreachable!("invalid transition: None -> None"),
+                        None => return Err(RenderError::InvalidTransition("None -> None")),
                         Some(s) => {
-                            // if there is a transition label, use that instead of the existing label
-                            if let Some(label) = &state.metadata.transition_label {
-                                writeln!(w, "  _initial_ -> {} [label=\"{}\"];", label, t)?
-                            } else {
-                                writeln!(w, "  _initial_ -> {} [label=\"{}\"];", s, t)?
-                            }
+                            // if there is a transition label, use that instead of the transition name
+                            let attributes =
+                                edge_attributes(Some(state.label_or(t)), color, f.label_mode);
+                            writeln!(w, "  _initial_ -> {}{};", s, attributes)?
                         }
                     },
                     Node::Decision(_) => {
                         // NOTE: unsure about this
-                        unreachable!("invalid transition: None -> Decision")
+                        return Err(RenderError::InvalidTransition("None -> Decision"));
                     }
                 }
             }
@@ -390,3 +971,509 @@ pub mod dot {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Export;
+    use crate::igraph::{tests::diamond, IntermediateGraph, Metadata, StateNode, Transition};
+
+    /// A node into `state`, annotated with `metadata`.
+    fn branch(state: &str, metadata: Metadata) -> StateNode<String> {
+        let mut node = StateNode::new(Some(state.to_string()));
+        node.update_metadata(metadata);
+        node
+    }
+
+    /// A bulb which is turned on through the `Switch` decision, as in `tests/pass/decision_metadata.rs`.
+    fn switch() -> IntermediateGraph<String, String> {
+        let mut graph = IntermediateGraph::new();
+        graph.add_state("Off".to_string());
+        graph.add_state("On".to_string());
+        graph.add_choice("Switch".to_string());
+        graph.add_transition(
+            None,
+            "screw".to_string().into(),
+            Some("Off".to_string()).into(),
+        );
+        graph.add_transition(
+            Some("Off".to_string()),
+            "unscrew".to_string().into(),
+            None.into(),
+        );
+        graph.add_transition(
+            Some("Off".to_string()),
+            Transition::new("turn_on".to_string()).with_color(Some("orange".to_string())),
+            Some("Switch".to_string()).into(),
+        );
+        graph.add_transition(
+            Some("On".to_string()),
+            "turn_off".to_string().into(),
+            Some("Off".to_string()).into(),
+        );
+        let on = branch(
+            "On",
+            Metadata {
+                transition_label: Some("bulb turned on".to_string()),
+                note: Some("the bulb works".to_string()),
+                priority: Some(1),
+                guard: Some("filament intact".to_string()),
+                ..Metadata::default()
+            },
+        );
+        let off = branch(
+            "Off",
+            Metadata {
+                transition_label: Some("bulb failed".to_string()),
+                note: Some("the bulb is broken\nreplace it".to_string()),
+                priority: Some(0),
+                color: Some("red".to_string()),
+                ..Metadata::default()
+            },
+        );
+        graph.add_transition(
+            Some("Switch".to_string()),
+            "Switch".to_string().into(),
+            vec![on, off].into(),
+        );
+        graph
+    }
+
+    /// The `Switch` decision, with a guard and no label on the `On` branch.
+    fn guarded_switch() -> IntermediateGraph<String, String> {
+        let mut graph = switch();
+        let on = branch(
+            "On",
+            Metadata {
+                guard: Some("filament intact".to_string()),
+                ..Metadata::default()
+            },
+        );
+        let off = branch("Off", Metadata::default());
+        graph.add_transition(
+            Some("Switch".to_string()),
+            "Switch".to_string().into(),
+            vec![on, off].into(),
+        );
+        graph
+    }
+
+    /// The automaton fields of a drone, listed in the diagrams by `shared_fields`.
+    fn shared_fields() -> IntermediateGraph<String, String> {
+        let mut graph = diamond();
+        graph.add_shared_field("x: f32".to_string());
+        graph.add_shared_field("y: f32".to_string());
+        graph
+    }
+
+    /// An initial transition into a decision, which no format can render.
+    fn initial_decision() -> IntermediateGraph<String, String> {
+        let mut graph = diamond();
+        graph.add_transition(
+            None,
+            "either".to_string().into(),
+            vec!["B".to_string(), "C".to_string()].into(),
+        );
+        graph
+    }
+
+    /// Render `graph` in the format `f`.
+    fn render<F: super::Format>(graph: &IntermediateGraph<String, String>, f: &F) -> String
+    where
+        IntermediateGraph<String, String>: Export<F>,
+    {
+        let mut out = Vec::<u8>::new();
+        graph.export(&mut out, f).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    #[cfg(all(feature = "dot", feature = "mermaid"))]
+    fn labels_agree() {
+        use super::{dot::Dot, mermaid::Mermaid, plantuml::PlantUml};
+
+        // the label replaces the transition name in every format
+        let mut graph = diamond();
+        let labeled = Metadata {
+            transition_label: Some("go left".to_string()),
+            ..Metadata::default()
+        };
+        graph.add_transition(
+            Some("A".to_string()),
+            "left".to_string().into(),
+            crate::igraph::Node::State(branch("B", labeled)),
+        );
+        assert!(render(&graph, &Dot::default()).contains("  A -> B [label=\"go left\"];\n"));
+        assert!(render(&graph, &Mermaid::default()).contains("A --> B : go left\n"));
+        assert!(render(&graph, &PlantUml::default()).contains("A --> B : go left\n"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "dot", feature = "mermaid"))]
+    fn weighted_label() {
+        use super::{dot::Dot, mermaid::Mermaid, plantuml::PlantUml};
+
+        // the weight is appended to the label as it is to the transition name
+        let mut graph = diamond();
+        let labeled = Metadata {
+            transition_label: Some("leap over".to_string()),
+            ..Metadata::default()
+        };
+        graph.add_transition(
+            Some("A".to_string()),
+            Transition::new("leap".to_string()).with_weight(Some(0.8)),
+            crate::igraph::Node::State(branch("D", labeled)),
+        );
+        assert!(render(&graph, &Dot::default()).contains("  A -> D [label=\"leap over (0.8)\"];\n"));
+        assert!(render(&graph, &Mermaid::default()).contains("A --> D : leap over (0.8)\n"));
+        assert!(render(&graph, &PlantUml::default()).contains("A --> D : leap over (0.8)\n"));
+    }
+
+    #[cfg(feature = "dot")]
+    mod dot {
+        use super::{diamond, initial_decision, render, switch};
+        use crate::igraph::export::dot::{Dot, LabelMode};
+        use crate::igraph::export::{Export, RenderError};
+        use crate::igraph::{IntermediateGraph, Transition};
+
+        #[test]
+        fn empty() {
+            let output = render(&IntermediateGraph::new(), &Dot::default());
+            assert!(output.starts_with("digraph Automata {\n"));
+            assert!(output.ends_with("}"));
+            assert!(!output.contains("->"));
+        }
+
+        #[test]
+        fn initial_decision_is_invalid() {
+            let result = initial_decision().export(&mut Vec::<u8>::new(), &Dot::default());
+            assert!(matches!(
+                result,
+                Err(RenderError::InvalidTransition("None -> Decision"))
+            ));
+        }
+
+        #[test]
+        fn colors() {
+            let output = render(&switch(), &Dot::default());
+            assert!(output.contains("  Off -> Switch [label=\"turn_on\", color=\"orange\"];\n"));
+            // the branches do not inherit the color of the transition into the decision
+            assert!(output.contains("  Switch -> Off [label=\"bulb failed\", color=\"red\"];\n"));
+            assert!(
+                output.contains("  Switch -> On [label=\"bulb turned on [filament intact]\"];\n")
+            );
+        }
+
+        #[test]
+        fn escape_colors() {
+            let mut graph = diamond();
+            graph.add_transition(
+                Some("D".to_string()),
+                Transition::new("loop".to_string())
+                    .with_color(Some(r#"red" penwidth="9"#.to_string())),
+                Some("A".to_string()).into(),
+            );
+            let output = render(&graph, &Dot::default());
+            assert!(output.contains(r#"  D -> A [label="loop", color="red\" penwidth=\"9"];"#));
+        }
+
+        #[test]
+        fn concentrate() {
+            assert!(!render(&diamond(), &Dot::default()).contains("concentrate=true;"));
+            let dot = Dot {
+                concentrate: true,
+                ..Dot::default()
+            };
+            assert!(render(&diamond(), &dot).contains("  concentrate=true;\n"));
+        }
+
+        #[test]
+        fn label_mode() {
+            let dot = Dot {
+                label_mode: LabelMode::Tooltip,
+                ..Dot::default()
+            };
+            assert!(render(&diamond(), &dot).contains("  A -> B [tooltip=\"left\"];\n"));
+            let dot = Dot {
+                label_mode: LabelMode::Both,
+                ..Dot::default()
+            };
+            assert!(
+                render(&diamond(), &dot).contains("  A -> B [label=\"left\", tooltip=\"left\"];\n")
+            );
+        }
+
+        #[test]
+        fn rankdir_and_ordering_edges() {
+            let output = render(&diamond(), &Dot::default());
+            assert!(!output.contains("rankdir"));
+            assert!(!output.contains("style=invis"));
+
+            let dot = Dot {
+                rankdir: Some("LR".to_string()),
+                ordering_edges: true,
+                ..Dot::default()
+            };
+            let output = render(&diamond(), &dot);
+            assert!(output.contains("  rankdir=LR;\n"));
+            assert!(output.contains(
+                "  A -> B [style=invis];\n  B -> C [style=invis];\n  C -> D [style=invis];\n"
+            ));
+        }
+
+        #[test]
+        fn escape_labels() {
+            let mut graph = diamond();
+            graph.add_transition(
+                Some("D".to_string()),
+                r#"say "hi" \o/"#.to_string().into(),
+                Some("A".to_string()).into(),
+            );
+            graph.add_shared_field(r#"quote: "\""#.to_string());
+            let dot = Dot {
+                label_mode: LabelMode::Both,
+                ..Dot::default()
+            };
+            let output = render(&graph, &dot);
+            assert!(output
+                .contains(r#"  D -> A [label="say \"hi\" \\o/", tooltip="say \"hi\" \\o/"];"#));
+            assert!(output.contains(r#"  _shared_ [label="quote: \"\\\"\l", shape=note];"#));
+        }
+
+        #[test]
+        fn unreachable_states() {
+            let mut graph = diamond();
+            graph.add_state("E".to_string());
+            assert!(!render(&graph, &Dot::default()).contains("fillcolor=gray"));
+            let dot = Dot {
+                shade_unreachable: true,
+                ..Dot::default()
+            };
+            let output = render(&graph, &dot);
+            assert_eq!(output.matches("fillcolor=gray").count(), 1);
+            assert!(output.contains("  E [style=filled, fillcolor=gray];\n"));
+        }
+
+        #[test]
+        fn rank_by_depth() {
+            assert!(!render(&diamond(), &Dot::default()).contains("rank=same"));
+            let dot = Dot {
+                rank_by_depth: true,
+                ..Dot::default()
+            };
+            let output = render(&diamond(), &dot);
+            // only the layers with more than one node are grouped
+            assert_eq!(output.matches("rank=same").count(), 1);
+            assert!(output.contains("  { rank=same; B; C; }\n"));
+        }
+    }
+
+    #[cfg(any(feature = "mermaid", feature = "html"))]
+    mod mermaid {
+        use super::{diamond, guarded_switch, initial_decision, render};
+        use crate::igraph::export::mermaid::Mermaid;
+        use crate::igraph::export::{Export, RenderError};
+        use crate::igraph::IntermediateGraph;
+
+        #[test]
+        fn empty() {
+            assert_eq!(
+                render(&IntermediateGraph::new(), &Mermaid::default()),
+                "stateDiagram-v2\n%% empty automaton\nstate \"(empty)\" as _empty_\n"
+            );
+        }
+
+        #[test]
+        fn initial_decision_is_invalid() {
+            let result = initial_decision().export(&mut Vec::<u8>::new(), &Mermaid::default());
+            assert!(matches!(
+                result,
+                Err(RenderError::InvalidTransition("None -> Decision"))
+            ));
+        }
+
+        #[test]
+        fn escape_labels() {
+            let mut graph = diamond();
+            graph.add_transition(
+                Some("D".to_string()),
+                r#"say "hi"; #1 <b>"#.to_string().into(),
+                Some("A".to_string()).into(),
+            );
+            assert!(render(&graph, &Mermaid::default())
+                .contains("D --> A : say #34;hi#34;#59; #35;1 #60;b#62;\n"));
+        }
+
+        #[test]
+        fn shared_fields() {
+            // the note is attached to the initial state
+            assert!(render(&super::shared_fields(), &Mermaid::default())
+                .contains("note left of A\n    x: f32\n    y: f32\nend note\n"));
+        }
+
+        #[test]
+        fn guard() {
+            let output = render(&guarded_switch(), &Mermaid::default());
+            assert!(output.contains("Switch --> On : [filament intact]\n"));
+            assert!(output.contains("Switch --> Off\n"));
+        }
+
+        #[test]
+        fn direction() {
+            assert!(!render(&diamond(), &Mermaid::default()).contains("direction"));
+            let mermaid = Mermaid {
+                direction: Some("LR".to_string()),
+                ..Mermaid::default()
+            };
+            assert!(render(&diamond(), &mermaid).starts_with("stateDiagram-v2\ndirection LR\n"));
+        }
+
+        #[test]
+        fn accepting_style() {
+            assert!(!render(&diamond(), &Mermaid::default()).contains("classDef"));
+            let mermaid = Mermaid {
+                accepting_style: Some("fill:#9f9".to_string()),
+                ..Mermaid::default()
+            };
+            let output = render(&diamond(), &mermaid);
+            assert!(output.contains("classDef accepting fill:#9f9\n"));
+            assert!(output.contains("class D accepting\n"));
+        }
+    }
+
+    mod plantuml {
+        use super::{diamond, guarded_switch, initial_decision, render, switch};
+        use crate::igraph::export::plantuml::{PlantUml, Theme};
+        use crate::igraph::export::{Export, RenderError};
+        use crate::igraph::IntermediateGraph;
+        use crate::igraph::{Metadata, Node, StateNode};
+
+        #[test]
+        fn empty() {
+            assert_eq!(
+                render(&IntermediateGraph::new(), &PlantUml::default()),
+                "@startuml\nhide empty description\n' empty automaton\nstate \"(empty)\" as _empty_\n@enduml\n"
+            );
+        }
+
+        #[test]
+        fn initial_decision_is_invalid() {
+            let result = initial_decision().export(&mut Vec::<u8>::new(), &PlantUml::default());
+            assert!(matches!(
+                result,
+                Err(RenderError::InvalidTransition("None -> Decision"))
+            ));
+        }
+
+        #[test]
+        fn decision_notes() {
+            let output = render(&switch(), &PlantUml::default());
+            assert!(output.contains(
+                "Switch -[#red]-> Off : bulb failed\nnote on link\n  the bulb is broken\n  replace it\nend note\n"
+            ));
+            assert!(output.contains("note on link\n  the bulb works\nend note\n"));
+        }
+
+        #[test]
+        fn colors() {
+            let output = render(&switch(), &PlantUml::default());
+            assert!(output.contains("Off -[#orange]-> Switch : turn_on\n"));
+            assert!(output.contains("Switch -[#red]-> Off : bulb failed\n"));
+        }
+
+        #[test]
+        fn guard() {
+            let output = render(&guarded_switch(), &PlantUml::default());
+            assert!(output.contains("Switch --> On : [filament intact]\n"));
+            assert!(output.contains("Switch --> Off\n"));
+        }
+
+        #[test]
+        fn decision_priority() {
+            // `On` is declared first, but `Off` has the lower priority
+            let output = render(&switch(), &PlantUml::default());
+            let off = output.find("Switch -[#red]-> Off").unwrap();
+            let on = output.find("Switch --> On").unwrap();
+            assert!(off < on);
+        }
+
+        #[test]
+        fn shared_fields() {
+            assert!(render(&super::shared_fields(), &PlantUml::default())
+                .contains("note as SharedFields\n  x: f32\n  y: f32\nend note\n"));
+        }
+
+        #[test]
+        fn transition_label_and_note() {
+            let mut graph = diamond();
+            let mut node = StateNode::new(None);
+            node.update_metadata(Metadata {
+                transition_label: Some("finish".to_string()),
+                note: Some("releases\nthe automaton".to_string()),
+                ..Metadata::default()
+            });
+            graph.add_transition(
+                Some("D".to_string()),
+                "end".to_string().into(),
+                Node::State(node),
+            );
+            assert!(render(&graph, &PlantUml::default()).contains(
+                "D --> [*] : finish\nnote on link\n  releases\n  the automaton\nend note\n"
+            ));
+        }
+
+        #[test]
+        fn theme() {
+            assert!(!render(&diamond(), &PlantUml::default()).contains("skinparam"));
+            let plantuml = PlantUml {
+                theme: Some(Theme::Dark),
+                ..PlantUml::default()
+            };
+            assert!(render(&diamond(), &plantuml).starts_with(
+                "@startuml\nhide empty description\nskinparam backgroundColor #2b2b2b\n"
+            ));
+        }
+    }
+
+    #[cfg(feature = "html")]
+    mod html {
+        use super::{diamond, render};
+        use crate::igraph::export::html::Html;
+        use crate::igraph::IntermediateGraph;
+
+        #[test]
+        fn mermaid_div() {
+            let output = render(&diamond(), &Html::default());
+            assert!(output.starts_with("<!DOCTYPE html>\n"));
+            assert!(output.contains("<div class=\"mermaid\">\nstateDiagram-v2\n"));
+            assert!(output.contains("A --&gt; B : left\n"));
+            assert!(output.ends_with("</div>\n</body>\n</html>\n"));
+        }
+
+        #[test]
+        fn empty() {
+            let output = render(&IntermediateGraph::new(), &Html::default());
+            assert!(output.contains("<div class=\"mermaid\">\nstateDiagram-v2\n"));
+            assert!(output.contains("state &quot;(empty)&quot; as _empty_\n</div>"));
+        }
+    }
+
+    mod json {
+        use super::{render, shared_fields};
+        use crate::igraph::export::json::Json;
+        use crate::igraph::IntermediateGraph;
+
+        #[test]
+        fn empty() {
+            assert_eq!(
+                render(&IntermediateGraph::new(), &Json),
+                r#"{"states":[],"choices":[],"transitions":[],"stats":{"states":0,"choices":0,"transitions":0}}"#
+            );
+        }
+
+        #[test]
+        fn shared_fields_listed() {
+            let output = render(&shared_fields(), &Json);
+            assert!(output.contains(r#"],"shared_fields":["x: f32","y: f32"],"stats":"#));
+        }
+    }
+}
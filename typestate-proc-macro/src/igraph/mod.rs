@@ -25,6 +25,39 @@ impl<S> StateNode<S> {
     pub fn update_metadata(&mut self, metadata: Metadata) {
         self.metadata = metadata;
     }
+
+    /// Return the transition label from the metadata, if any.
+    pub fn transition_label(&self) -> Option<&str> {
+        self.metadata.transition_label.as_deref()
+    }
+
+    /// Return the label of a decision branch, i.e. the transition label followed by the `[guard]`, if any.
+    pub fn branch_label(&self) -> Option<String> {
+        match (self.transition_label(), &self.metadata.guard) {
+            (Some(label), Some(guard)) => Some(format!("{} [{}]", label, guard)),
+            (Some(label), None) => Some(label.to_string()),
+            (None, Some(guard)) => Some(format!("[{}]", guard)),
+            (None, None) => None,
+        }
+    }
+
+    /// Return the color from the metadata, if any.
+    pub fn color(&self) -> Option<&str> {
+        self.metadata.color.as_deref()
+    }
+
+    /// Return the label of the transition `t` into this node, i.e. the transition label from
+    /// the metadata or, if there is none, the transition itself, followed by its weight, if any.
+    pub fn label_or<T>(&self, t: &Transition<T>) -> String
+    where
+        T: Hash + Eq + Debug + Clone + Display,
+    {
+        match (self.transition_label(), t.weight) {
+            (Some(label), Some(weight)) => format!("{} ({})", label, weight),
+            (Some(label), None) => label.to_string(),
+            (None, _) => t.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -67,17 +100,23 @@ impl<S> From<Vec<StateNode<S>>> for Node<S>
 where
     S: Hash + Eq + Debug + Clone + Display,
 {
-    fn from(s: Vec<StateNode<S>>) -> Self {
+    fn from(mut s: Vec<StateNode<S>>) -> Self {
+        // branches with a priority come first, in ascending order, the others keep their declaration order
+        s.sort_by_key(|state| state.metadata.priority.map_or((1, 0), |p| (0, p)));
         Node::Decision(s)
     }
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Transition<T>
 where
     T: Hash + Eq + Debug + Clone + Display,
 {
     transition: T,
+    /// Weight (e.g. probability) of the transition, appended to its label.
+    weight: Option<f64>,
+    /// Color of the transition edge.
+    color: Option<String>,
 }
 
 impl<T> Transition<T>
@@ -85,7 +124,46 @@ where
     T: Hash + Eq + Debug + Clone + Display,
 {
     pub fn new(transition: T) -> Self {
-        Self { transition }
+        Self {
+            transition,
+            weight: None,
+            color: None,
+        }
+    }
+
+    pub fn with_weight(mut self, weight: Option<f64>) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn with_color(mut self, color: Option<String>) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn color(&self) -> Option<&str> {
+        self.color.as_deref()
+    }
+}
+
+// the weight is an annotation, transitions are identified by their value only
+impl<T> PartialEq for Transition<T>
+where
+    T: Hash + Eq + Debug + Clone + Display,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.transition == other.transition
+    }
+}
+
+impl<T> Eq for Transition<T> where T: Hash + Eq + Debug + Clone + Display {}
+
+impl<T> Hash for Transition<T>
+where
+    T: Hash + Eq + Debug + Clone + Display,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.transition.hash(state);
     }
 }
 
@@ -94,7 +172,11 @@ where
     T: Hash + Eq + Debug + Clone + Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{}", self.transition))
+        if let Some(weight) = self.weight {
+            f.write_fmt(format_args!("{} ({})", self.transition, weight))
+        } else {
+            f.write_fmt(format_args!("{}", self.transition))
+        }
     }
 }
 
@@ -109,18 +191,62 @@ where
 
 /// Metadata associated with nodes and transitions,
 /// to be used as additional annotations.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, FromMeta)]
+#[derive(Debug, Clone, PartialEq, FromMeta)]
+#[darling(default)]
 pub struct Metadata {
     #[darling(rename = "label")]
     transition_label: Option<String>,
+    /// Note attached to the transition, currently only rendered by PlantUML.
+    note: Option<String>,
+    /// Order of the branch inside a decision, lower values come first.
+    /// Only supported on decision branches.
+    priority: Option<i64>,
+    /// Weight of a transition, appended to the transition label.
+    /// Only supported on transitions.
+    weight: Option<f64>,
+    /// Color of the transition edge, used to categorize transitions.
+    #[darling(with = "parse_color")]
+    color: Option<String>,
+    /// Condition under which a decision branch is taken, rendered as `[guard]`.
+    /// Only supported on decision branches.
+    guard: Option<String>,
+}
+
+/// Parse a color, either a name (e.g. `red`) or a hex code (e.g. `#ff0000`),
+/// rejecting anything else since PlantUML interpolates it into the arrow (e.g. `-[#red]->`).
+fn parse_color(meta: &syn::Meta) -> darling::Result<Option<String>> {
+    let color = String::from_meta(meta)?;
+    let code = color.trim_start_matches('#');
+    if !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Ok(Some(color))
+    } else {
+        Err(darling::Error::custom(format!(
+            "Invalid color `{}`, expected a name (e.g. `red`) or a hex code (e.g. `#ff0000`).",
+            color
+        ))
+        .with_span(meta))
+    }
 }
 
 impl Metadata {
     fn empty() -> Self {
         Self {
             transition_label: None,
+            note: None,
+            priority: None,
+            weight: None,
+            color: None,
+            guard: None,
         }
     }
+
+    pub fn weight(&self) -> Option<f64> {
+        self.weight
+    }
+
+    pub fn color(&self) -> Option<String> {
+        self.color.clone()
+    }
 }
 
 impl Default for Metadata {
@@ -139,7 +265,11 @@ where
 {
     states: HashSet<S>,
     choices: HashSet<S>,
+    /// States explicitly marked as final, without a transition leading out of the automaton.
+    final_states: HashSet<S>,
     delta: HashMap<Option<S>, HashMap<Transition<T>, Node<S>>>,
+    /// Fields shared by every state (i.e. the automaton fields), rendered as a note.
+    shared_fields: Vec<String>,
 }
 
 impl<S, T> IntermediateGraph<S, T>
@@ -151,7 +281,9 @@ where
         Self {
             states: HashSet::new(),
             choices: HashSet::new(),
+            final_states: HashSet::new(),
             delta: HashMap::new(),
+            shared_fields: Vec::new(),
         }
     }
 
@@ -163,6 +295,143 @@ where
         self.choices.insert(choice)
     }
 
+    pub fn add_final_state(&mut self, state: S) -> bool {
+        self.final_states.insert(state)
+    }
+
+    pub fn add_shared_field(&mut self, field: String) {
+        self.shared_fields.push(field);
+    }
+
+    /// Return the number of states, excluding the choices.
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Return the number of choices.
+    pub fn choice_count(&self) -> usize {
+        self.choices.len()
+    }
+
+    /// Return the number of transitions, including the initial and final ones.
+    /// States marked with `#[final_state]` have no final transition and are not counted.
+    pub fn transition_count(&self) -> usize {
+        self.delta.values().map(HashMap::len).sum()
+    }
+
+    /// Return the transitions leaving `source` (`None` for the initial transitions)
+    /// along with their destinations, sorted by transition name.
+    pub fn transitions_from(&self, source: &Option<S>) -> Vec<(&Transition<T>, &Node<S>)> {
+        let mut transitions: Vec<_> = self.delta.get(source).into_iter().flatten().collect();
+        transitions.sort_by_key(|(transition, _)| transition.transition.to_string());
+        transitions
+    }
+
+    /// Return the states and choices grouped by their breadth-first depth from the initial transitions,
+    /// following the transitions by name and decision branches by their order.
+    /// The states which cannot be reached are not included.
+    pub fn bfs_layers(&self) -> Vec<Vec<&S>> {
+        let mut layers: Vec<Vec<&S>> = vec![];
+        let mut visited: HashSet<&S> = HashSet::new();
+        let mut sources: Vec<Option<&S>> = vec![None];
+        while !sources.is_empty() {
+            let mut layer = vec![];
+            for src in sources {
+                for (_, dst) in self.transitions_from(&src.cloned()) {
+                    let states: Vec<&S> = match dst {
+                        Node::State(state) => state.state.iter().collect(),
+                        Node::Decision(decision) => {
+                            decision.iter().filter_map(|s| s.state.as_ref()).collect()
+                        }
+                    };
+                    for state in states {
+                        if visited.insert(state) {
+                            layer.push(state);
+                        }
+                    }
+                }
+            }
+            sources = layer.iter().map(|s| Some(*s)).collect();
+            if !layer.is_empty() {
+                layers.push(layer);
+            }
+        }
+        layers
+    }
+
+    /// Return the states and choices in breadth-first order from the initial transitions,
+    /// following the transitions by name and decision branches by their order.
+    /// The states which cannot be reached come last, sorted by name.
+    pub fn bfs_order(&self) -> Vec<&S> {
+        let mut order: Vec<&S> = self.bfs_layers().into_iter().flatten().collect();
+        let visited: HashSet<&S> = order.iter().copied().collect();
+        let mut unreachable: Vec<&S> = self
+            .states
+            .iter()
+            .chain(&self.choices)
+            .filter(|s| !visited.contains(s))
+            .collect();
+        unreachable.sort_by_key(|s| s.to_string());
+        order.extend(unreachable);
+        order
+    }
+
+    /// Return the transitions leaving `state`, sorted by name.
+    pub fn outgoing_transitions(&self, state: &S) -> Vec<&T> {
+        let mut transitions: Vec<_> = self
+            .delta
+            .get(&Some(state.clone()))
+            .into_iter()
+            .flat_map(HashMap::keys)
+            .map(|transition| &transition.transition)
+            .collect();
+        transitions.sort_by_key(|transition| transition.to_string());
+        transitions
+    }
+
+    /// Return the transitions between states, sorted by source, transition and destination.
+    /// Transitions into a choice are replaced by a transition into each of its branches.
+    pub fn state_transitions(&self) -> Vec<(&S, &T, &S)> {
+        let mut transitions = vec![];
+        for (src, v) in &self.delta {
+            let src = match src {
+                Some(src) if !self.choices.contains(src) => src,
+                _ => continue,
+            };
+            for (t, dst) in v {
+                if let Node::State(StateNode {
+                    state: Some(dst), ..
+                }) = dst
+                {
+                    if self.choices.contains(dst) {
+                        let branches = self
+                            .delta
+                            .get(&Some(dst.clone()))
+                            .into_iter()
+                            .flat_map(HashMap::values)
+                            .flat_map(|node| match node {
+                                Node::Decision(decision) => {
+                                    decision.iter().filter_map(|s| s.state.as_ref()).collect()
+                                }
+                                Node::State(_) => vec![],
+                            });
+                        transitions.extend(branches.map(|branch| (src, &t.transition, branch)));
+                    } else {
+                        transitions.push((src, &t.transition, dst));
+                    }
+                }
+            }
+        }
+        transitions.sort_by_key(|(src, t, dst)| (src.to_string(), t.to_string(), dst.to_string()));
+        transitions
+    }
+
+    /// Return the states (and choices) reachable from the initial transitions.
+    #[cfg(feature = "dot")]
+    pub fn reachable_states(&self) -> HashSet<&S> {
+        self.bfs_layers().into_iter().flatten().collect()
+    }
+
     pub fn add_transition(
         &mut self,
         source: Option<S>,
@@ -190,3 +459,47 @@ where
         Self::new()
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::IntermediateGraph;
+
+    /// `A` branches into `B` and `C`, which both lead to `D`, the only state which can end the automaton.
+    pub(crate) fn diamond() -> IntermediateGraph<String, String> {
+        let mut graph = IntermediateGraph::new();
+        for state in ["A", "B", "C", "D"] {
+            graph.add_state(state.to_string());
+        }
+        let mut add = |src: Option<&str>, t: &str, dst: Option<&str>| {
+            graph.add_transition(
+                src.map(str::to_string),
+                t.to_string().into(),
+                dst.map(str::to_string).into(),
+            )
+        };
+        add(None, "start", Some("A"));
+        add(Some("A"), "left", Some("B"));
+        add(Some("A"), "right", Some("C"));
+        add(Some("B"), "join", Some("D"));
+        add(Some("C"), "join", Some("D"));
+        add(Some("D"), "end", None);
+        graph
+    }
+
+    #[test]
+    fn transition_count() {
+        let mut graph = diamond();
+        assert_eq!(graph.transition_count(), 6);
+        graph.add_final_state("C".to_string());
+        assert_eq!(graph.transition_count(), 6);
+    }
+
+    #[test]
+    fn bfs_order() {
+        let mut graph = diamond();
+        graph.add_state("F".to_string());
+        graph.add_state("E".to_string());
+        // the unreachable states come last, sorted by name
+        assert_eq!(graph.bfs_order(), ["A", "B", "C", "D", "E", "F"]);
+    }
+}
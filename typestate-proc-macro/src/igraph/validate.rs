@@ -50,6 +50,22 @@ where
     S: Hash + Eq + Debug + Clone + Display,
     T: Hash + Eq + Debug + Clone + Display,
 {
+    /// Return the states reachable from `state` through a single transition.
+    pub fn successors<'a>(&'a self, state: &S) -> impl Iterator<Item = &'a S> {
+        self.delta
+            .get(state)
+            .into_iter()
+            .flat_map(|transitions| transitions.values().flat_map(HashSet::iter))
+    }
+
+    /// Return the states which reach `state` through a single transition.
+    pub fn predecessors<'a>(&'a self, state: &S) -> impl Iterator<Item = &'a S> {
+        self.idelta
+            .get(state)
+            .into_iter()
+            .flat_map(|transitions| transitions.values().flat_map(HashSet::iter))
+    }
+
     #[allow(clippy::shadow_unrelated)]
     fn add_transition(&mut self, src: S, transition: Transition<T>, dst: S) {
         // HACK: since `delta` and `idelta` are inside `self`, use the macro to as a "function"
@@ -94,6 +110,7 @@ where
     fn from(i: IntermediateGraph<S, T>) -> Self {
         let mut s = Self {
             states: i.states,
+            final_states: i.final_states,
             ..GenericAutomaton::default()
         };
         // NOTE: maybe add the choices
@@ -144,13 +161,7 @@ where
         let mut productive = HashSet::new();
         while let Some(state) = stack.pop_back() {
             if productive.insert(state.clone()) {
-                if let Some(states) = self.idelta.get(state).map(|transitions| {
-                    transitions
-                        .values()
-                        .flat_map(std::collections::HashSet::iter)
-                }) {
-                    stack.extend(states)
-                }
+                stack.extend(self.predecessors(state))
             }
         }
         productive
@@ -175,55 +186,86 @@ where
     }
 }
 
-/// Useful states property type.
-pub struct UsefulStates;
+/// Reachable states property type.
+///
+/// The states reachable from any of the initial states.
+pub struct ReachableStates;
 
-impl Property for UsefulStates {}
+impl Property for ReachableStates {}
 
-impl<S, T> Validate<UsefulStates> for GenericAutomaton<S, T>
+impl<S, T> Validate<ReachableStates> for GenericAutomaton<S, T>
 where
     S: Hash + Eq + Debug + Clone + Display,
     T: Hash + Eq + Debug + Clone + Display,
 {
     type Out = HashSet<S>;
 
-    fn validate(&self, _: UsefulStates) -> Self::Out {
-        // TODO this could benefit from some "caching" of results on productive
-        let productive = self.validate(ProductiveStates);
+    fn validate(&self, _: ReachableStates) -> Self::Out {
         let mut stack: VecDeque<_> = self.initial_states.iter().collect();
-        // productive == visited
+        // reachable == visited
         let mut reachable = HashSet::new();
         while let Some(state) = stack.pop_back() {
             if reachable.insert(state.clone()) {
-                if let Some(states) = self.delta.get(state).map(|transitions| {
-                    transitions
-                        .values()
-                        .flat_map(std::collections::HashSet::iter)
-                }) {
-                    stack.extend(states)
-                }
+                stack.extend(self.successors(state))
             }
         }
-        productive.intersection(&reachable).cloned().collect()
+        reachable
     }
 }
 
-/// Non-useful states property type.
-pub struct NonUsefulStates;
+/// Unreachable states property type.
+///
+/// The states that no initial state (i.e. no constructor) can reach.
+pub struct UnreachableStates;
 
-impl Property for NonUsefulStates {}
+impl Property for UnreachableStates {}
 
-impl<S, T> Validate<NonUsefulStates> for GenericAutomaton<S, T>
+impl<S, T> Validate<UnreachableStates> for GenericAutomaton<S, T>
 where
     S: Hash + Eq + Debug + Clone + Display,
     T: Hash + Eq + Debug + Clone + Display,
 {
     type Out = HashSet<S>;
 
-    fn validate(&self, _: NonUsefulStates) -> Self::Out {
-        self.states
-            .difference(&self.validate(UsefulStates))
-            .cloned()
-            .collect()
+    fn validate(&self, _: UnreachableStates) -> Self::Out {
+        let reachable = self.validate(ReachableStates);
+        self.states.difference(&reachable).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GenericAutomaton, ReachableStates, Validate};
+    use crate::igraph::tests::diamond;
+    use std::collections::HashSet;
+
+    #[test]
+    fn reachable_states() {
+        let mut graph = diamond();
+        graph.add_state("E".to_string());
+        graph.add_state("F".to_string());
+        // `E` is only reachable through its own constructor
+        graph.add_transition(
+            None,
+            "other".to_string().into(),
+            Some("E".to_string()).into(),
+        );
+        let automaton = GenericAutomaton::from(graph);
+        let expected: HashSet<_> = ["A", "B", "C", "D", "E"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(automaton.validate(ReachableStates), expected);
+    }
+
+    #[test]
+    fn successors() {
+        let automaton = GenericAutomaton::from(diamond());
+        let successors: HashSet<_> = automaton.successors(&"A".to_string()).collect();
+        assert_eq!(successors, [&"B".to_string(), &"C".to_string()].into());
+        let predecessors: HashSet<_> = automaton.predecessors(&"D".to_string()).collect();
+        assert_eq!(predecessors, [&"B".to_string(), &"C".to_string()].into());
+        // the final transitions are not followed
+        assert_eq!(automaton.successors(&"D".to_string()).count(), 0);
     }
 }
@@ -3,7 +3,7 @@ mod visitors;
 
 use crate::{
     igraph::{
-        validate::{GenericAutomaton, NonProductiveStates, NonUsefulStates, Validate},
+        validate::{GenericAutomaton, NonProductiveStates, UnreachableStates, Validate},
         IntermediateGraph,
     },
     visitors::state::AUTOMATA_ATTR_IDENT,
@@ -17,8 +17,8 @@ use std::{
     hash::Hash,
 };
 use syn::{
-    parse_macro_input, Attribute, AttributeArgs, Error, Ident, Item, ItemEnum, ItemMod, ItemStruct,
-    ItemTrait, Variant,
+    ext::IdentExt, parse_macro_input, Attribute, AttributeArgs, Error, Fields, Ident, Item,
+    ItemEnum, ItemMod, ItemStruct, ItemTrait, Path, Variant,
 };
 
 const CRATE_NAME: &str = "typestate_proc_macro";
@@ -67,6 +67,12 @@ pub fn typestate(args: TokenStream, input: TokenStream) -> TokenStream {
         TOption::None => None,
     };
 
+    let initial_constructors_ident = match args.initial_constructors {
+        TOption::Some(string) => Some(format_ident!("{}", string)),
+        TOption::Default => Some(format_ident!("new")),
+        TOption::None => None,
+    };
+
     // parse the input as a mod
     let mut module: ItemMod = parse_macro_input!(input);
 
@@ -90,8 +96,68 @@ pub fn typestate(args: TokenStream, input: TokenStream) -> TokenStream {
         &mut state_machine_info
     ));
 
-    #[cfg(any(feature = "dot", feature = "plantuml"))]
-    export_diagram_files(&state_machine_info);
+    if args.shared_fields {
+        if let Some(automaton) = &state_machine_info.automaton_ident {
+            for field in &automaton.fields {
+                if let Some(ident) = &field.ident {
+                    let ty = &field.ty;
+                    state_machine_info
+                        .intermediate_automaton
+                        .add_shared_field(format!("{}: {}", ident, ty.to_token_stream()));
+                }
+            }
+        }
+    }
+
+    #[cfg(any(feature = "dot", feature = "plantuml", feature = "html"))]
+    if let Err(err) = export_diagram_files(&state_machine_info) {
+        return Error::new(Span::call_site(), err)
+            .into_compile_error()
+            .into();
+    }
+
+    let mut initial_constructors: Vec<Item> = vec![];
+    if let Some(constructor_ident) = &initial_constructors_ident {
+        bail_if_any!(state_machine_info.check_constructor_clashes(constructor_ident));
+        initial_constructors.expand_initial_constructors(constructor_ident, &state_machine_info);
+    }
+
+    let mut transition_enums: Vec<Item> = vec![];
+    if args.generate_transition_enum {
+        bail_if_any!(state_machine_info.check_transition_enum_clashes());
+        transition_enums.expand_transition_enums(&state_machine_info);
+    }
+
+    let mut transition_table: Vec<Item> = vec![];
+    if args.transition_table {
+        transition_table.expand_transition_table(&state_machine_info);
+    }
+
+    let mut from_transitions: Vec<Item> = vec![];
+    if args.generate_from {
+        from_transitions.expand_from_transitions(&state_machine_info);
+    }
+
+    let mut embedded_automaton: Vec<Item> = vec![];
+    if args.embed_automaton {
+        use igraph::export::{json::Json, Export};
+        let mut f = Vec::<u8>::new();
+
+        if let Err(err) = state_machine_info
+            .intermediate_automaton
+            .export(&mut f, &Json)
+        {
+            return Error::new(Span::call_site(), err)
+                .into_compile_error()
+                .into();
+        }
+
+        let automaton = String::from_utf8(f).unwrap();
+        embedded_automaton.push(::syn::parse_quote!(
+            /// JSON description of the automaton states and transitions.
+            pub const AUTOMATON: &str = #automaton;
+        ));
+    }
 
     #[cfg(feature = "mermaid")]
     {
@@ -99,11 +165,14 @@ pub fn typestate(args: TokenStream, input: TokenStream) -> TokenStream {
         // NOTE: hacky bypass to avoid rewriting the ::Write
         let mut f = Vec::<u8>::new();
 
-        // TODO: handle the unwrap
-        state_machine_info
+        if let Err(err) = state_machine_info
             .intermediate_automaton
-            .export(&mut f, Mermaid)
-            .unwrap();
+            .export(&mut f, &Mermaid::from_env())
+        {
+            return Error::new(Span::call_site(), err)
+                .into_compile_error()
+                .into();
+        }
 
         let doc_string = String::from_utf8(f).unwrap();
         let doc_string_iter = doc_string.split('\n').filter(|s| !s.is_empty());
@@ -128,10 +197,15 @@ pub fn typestate(args: TokenStream, input: TokenStream) -> TokenStream {
         .collect();
     bail_if_any!(errors);
 
-    let errors: Vec<Error> = ga
-        .validate(NonUsefulStates)
+    let mut unreachable = ga
+        .validate(UnreachableStates)
         .into_iter()
-        .map(|ident| TypestateError::NonUsefulState(ident).into())
+        .collect::<Vec<_>>();
+    // sort to keep the diagnostics order stable across compilations
+    unreachable.sort_by_key(|ident| ident.to_string());
+    let errors: Vec<Error> = unreachable
+        .into_iter()
+        .map(|ident| TypestateError::UnreachableState(ident).into())
         .collect();
     bail_if_any!(errors);
 
@@ -156,14 +230,21 @@ pub fn typestate(args: TokenStream, input: TokenStream) -> TokenStream {
 
     if let Some((_, v)) = &mut module.content {
         v.append(&mut enumerate_tokens);
+        v.append(&mut initial_constructors);
+        v.append(&mut transition_enums);
+        v.append(&mut transition_table);
+        v.append(&mut from_transitions);
+        v.append(&mut embedded_automaton);
     }
 
     // if errors do not exist, return the token stream
     module.into_token_stream().into()
 }
 
-#[cfg(any(feature = "dot", feature = "plantuml"))]
-fn export_diagram_files(state_machine_info: &StateMachineInfo) {
+#[cfg(any(feature = "dot", feature = "plantuml", feature = "html"))]
+fn export_diagram_files(
+    state_machine_info: &StateMachineInfo,
+) -> Result<(), igraph::export::RenderError> {
     use igraph::export::Export;
 
     let folder_path = ::std::env::var_os("EXPORT_FOLDER")
@@ -173,45 +254,80 @@ fn export_diagram_files(state_machine_info: &StateMachineInfo) {
     #[cfg(feature = "dot")]
     {
         use igraph::export::dot::Dot;
-        use std::fs::File;
-
-        let mut f = File::create(format!(
-            "{}{}.dot",
-            folder_path,
-            state_machine_info.automaton_ident.clone().unwrap().ident
-        ))
-        .unwrap();
 
+        let mut f = Vec::<u8>::new();
         state_machine_info
             .intermediate_automaton
             .clone()
-            .export(&mut f, Dot)
-            .unwrap();
+            .export(&mut f, &Dot::from_env())?;
+
+        write_if_changed(
+            &format!(
+                "{}{}.dot",
+                folder_path,
+                state_machine_info.automaton_ident.clone().unwrap().ident
+            ),
+            &f,
+        )?;
     }
 
     #[cfg(feature = "plantuml")]
     {
         use igraph::export::plantuml::PlantUml;
-        use std::fs::File;
 
-        let mut f = File::create(format!(
-            "{}{}.uml",
-            folder_path,
-            state_machine_info.automaton_ident.clone().unwrap().ident
-        ))
-        .unwrap();
+        let mut f = Vec::<u8>::new();
+        state_machine_info
+            .intermediate_automaton
+            .clone()
+            .export(&mut f, &PlantUml::from_env())?;
+
+        write_if_changed(
+            &format!(
+                "{}{}.uml",
+                folder_path,
+                state_machine_info.automaton_ident.clone().unwrap().ident
+            ),
+            &f,
+        )?;
+    }
+
+    #[cfg(feature = "html")]
+    {
+        use igraph::export::html::Html;
 
+        let mut f = Vec::<u8>::new();
         state_machine_info
             .intermediate_automaton
             .clone()
-            .export(&mut f, PlantUml)
-            .unwrap();
+            .export(&mut f, &Html::from_env())?;
+
+        write_if_changed(
+            &format!(
+                "{}{}.html",
+                folder_path,
+                state_machine_info.automaton_ident.clone().unwrap().ident
+            ),
+            &f,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write `contents` to `path`, unless the file already holds them.
+/// Unchanged diagrams are left untouched, so file watchers are not triggered on every build.
+#[cfg(any(feature = "dot", feature = "plantuml", feature = "html"))]
+fn write_if_changed(path: &str, contents: &[u8]) -> ::std::io::Result<()> {
+    if ::std::fs::read(path).map_or(true, |previous| previous != contents) {
+        ::std::fs::write(path, contents)?;
     }
+    Ok(())
 }
 
 trait ExpandEnumerate {
     fn expand_enumerate(&mut self, automata: &Ident, automata_enum: &Ident, states: &[&Ident]);
-    /// Expand the [`ToString`] implentation for enumeration.
+    /// Expand the [`Display`](std::fmt::Display) implentation for enumeration,
+    /// which in turn provides [`ToString`].
     /// Only available with `std` and when `enumerate` is used.
     fn expand_to_string(&mut self, automata_enum: &Ident, states: &[&Ident]);
     /// Expand the enumeration containing all states.
@@ -237,10 +353,10 @@ impl ExpandEnumerate for Vec<Item> {
 
     fn expand_to_string(&mut self, automata_enum: &Ident, states: &[&Ident]) {
         let to_string = ::quote::quote! {
-            impl ::std::string::ToString for #automata_enum {
-                fn to_string(&self) -> String {
+            impl ::core::fmt::Display for #automata_enum {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
                     match &self {
-                        #(#automata_enum::#states(_) => stringify!(#states).to_string(),)*
+                        #(#automata_enum::#states(_) => f.write_str(stringify!(#states)),)*
                     }
                 }
             }
@@ -274,6 +390,211 @@ impl ExpandEnumerate for Vec<Item> {
     }
 }
 
+trait ExpandInitialConstructors {
+    /// Expand a constructor for each initial state, taking the automaton fields as arguments,
+    /// along with the state itself when it has fields.
+    /// Only available when `initial_constructors` is used.
+    fn expand_initial_constructors(&mut self, constructor_ident: &Ident, info: &StateMachineInfo);
+}
+
+impl ExpandInitialConstructors for Vec<Item> {
+    fn expand_initial_constructors(&mut self, constructor_ident: &Ident, info: &StateMachineInfo) {
+        let automaton = info.automaton_ident.as_ref().unwrap();
+        let automaton_ident = &automaton.ident;
+        let field_ident = automaton
+            .fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+            .collect::<Vec<_>>();
+        let field_ty = automaton
+            .fields
+            .iter()
+            .map(|field| &field.ty)
+            .collect::<Vec<_>>();
+        let generated_attr = generated_attr();
+
+        // initial non-deterministic states are skipped, they are not valid `State` parameters
+        let mut initial_states = info
+            .initial_states
+            .keys()
+            .filter_map(|state| info.det_states.get(state))
+            .collect::<Vec<_>>();
+        initial_states.sort_by_key(|state| state.ident.to_string());
+
+        for state in initial_states {
+            let state_ident = &state.ident;
+            let (state_arg, state_value) = match &state.fields {
+                Fields::Unit => (None, ::quote::quote!(#state_ident)),
+                Fields::Named(named) if named.named.is_empty() => {
+                    (None, ::quote::quote!(#state_ident {}))
+                }
+                Fields::Unnamed(unnamed) if unnamed.unnamed.is_empty() => {
+                    (None, ::quote::quote!(#state_ident()))
+                }
+                _ => (
+                    Some(::quote::quote!(state: #state_ident)),
+                    ::quote::quote!(state),
+                ),
+            };
+            self.push(::syn::parse_quote! {
+                #generated_attr
+                impl #automaton_ident<#state_ident> {
+                    pub fn #constructor_ident(#(#field_ident: #field_ty,)* #state_arg) -> Self {
+                        Self {
+                            #(#field_ident,)*
+                            state: #state_value,
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+trait ExpandTransitionEnums {
+    /// Expand, for each state, an enumeration of its outgoing transitions
+    /// and a `transitions` method listing them.
+    /// Only available when `generate_transition_enum` is used.
+    fn expand_transition_enums(&mut self, info: &StateMachineInfo);
+}
+
+impl ExpandTransitionEnums for Vec<Item> {
+    fn expand_transition_enums(&mut self, info: &StateMachineInfo) {
+        let automaton_ident = info.get_automaton_ident();
+        let generated_attr = generated_attr();
+
+        let mut states = info.det_states.keys().collect::<Vec<_>>();
+        states.sort_by_key(|state| state.to_string());
+
+        for state in states {
+            let enum_ident = format_ident!("{}Transition", state);
+            // `take_off` => `TakeOff`, `r#move` => `Move`
+            let variants = info
+                .intermediate_automaton
+                .outgoing_transitions(state)
+                .into_iter()
+                .map(|transition| {
+                    let variant: String = transition
+                        .unraw()
+                        .to_string()
+                        .split('_')
+                        .filter(|word| !word.is_empty())
+                        .map(|word| {
+                            let mut chars = word.chars();
+                            chars.next().map_or_else(String::new, |first| {
+                                first.to_uppercase().chain(chars).collect()
+                            })
+                        })
+                        .collect();
+                    format_ident!("{}", variant)
+                })
+                .collect::<Vec<_>>();
+            self.push(::syn::parse_quote! {
+                #generated_attr
+                #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+                pub enum #enum_ident {
+                    #(#variants,)*
+                }
+            });
+            self.push(::syn::parse_quote! {
+                #generated_attr
+                impl #automaton_ident<#state> {
+                    /// The transitions available from the current state.
+                    pub fn transitions(&self) -> &'static [#enum_ident] {
+                        &[#(#enum_ident::#variants,)*]
+                    }
+                }
+            });
+        }
+    }
+}
+
+trait ExpandTransitionTable {
+    /// Expand the `StateId` enumeration, with a variant per state,
+    /// and the `transition_table` function listing the transitions between states.
+    /// Only available when `transition_table` is used.
+    fn expand_transition_table(&mut self, info: &StateMachineInfo);
+}
+
+impl ExpandTransitionTable for Vec<Item> {
+    fn expand_transition_table(&mut self, info: &StateMachineInfo) {
+        let generated_attr = generated_attr();
+
+        let mut states = info.det_states.keys().collect::<Vec<_>>();
+        states.sort_by_key(|state| state.to_string());
+
+        let (sources, (transitions, destinations)): (Vec<_>, (Vec<_>, Vec<_>)) = info
+            .intermediate_automaton
+            .state_transitions()
+            .into_iter()
+            .map(|(src, t, dst)| (src, (t.to_string(), dst)))
+            .unzip();
+
+        self.push(::syn::parse_quote! {
+            #generated_attr
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub enum StateId {
+                #(#states,)*
+            }
+        });
+        self.push(::syn::parse_quote! {
+            #generated_attr
+            /// The transitions between states, as `(source, transition, destination)`.
+            pub fn transition_table() -> &'static [(StateId, &'static str, StateId)] {
+                &[#((StateId::#sources, #transitions, StateId::#destinations),)*]
+            }
+        });
+    }
+}
+
+trait ExpandFromTransitions {
+    /// Expand a `From<Automaton<Source>>` implementation for `Automaton<Destination>`
+    /// calling the transition between them, for each transition which only takes `self` and has no guard.
+    /// States joined by several such transitions are skipped, as the implementation would be ambiguous.
+    /// Only available when `generate_from` is used.
+    fn expand_from_transitions(&mut self, info: &StateMachineInfo);
+}
+
+impl ExpandFromTransitions for Vec<Item> {
+    fn expand_from_transitions(&mut self, info: &StateMachineInfo) {
+        let automaton_ident = info.get_automaton_ident();
+        let generated_attr = generated_attr();
+
+        // transitions into choices are not infallible and self-loops overlap with `impl<T> From<T> for T`
+        let mut transitions = info
+            .plain_transitions
+            .iter()
+            .filter(|t| t.source != t.destination && info.det_states.contains_key(&t.destination))
+            .collect::<Vec<_>>();
+        transitions.sort_by_key(|t| (t.source.to_string(), t.symbol.to_string()));
+
+        for transition in &transitions {
+            let Transition {
+                source,
+                destination,
+                symbol,
+            } = transition;
+            if transitions
+                .iter()
+                .filter(|t| &t.source == source && &t.destination == destination)
+                .count()
+                > 1
+            {
+                continue;
+            }
+            let source_trait = format_ident!("{}State", source);
+            self.push(::syn::parse_quote! {
+                #generated_attr
+                impl From<#automaton_ident<#source>> for #automaton_ident<#destination> {
+                    fn from(automaton: #automaton_ident<#source>) -> Self {
+                        <#automaton_ident<#source> as #source_trait>::#symbol(automaton)
+                    }
+                }
+            });
+        }
+    }
+}
+
 /// Option-like triplet. Used in argument parsing to differ between:
 /// - Missing value `#[]`
 /// - Concrete value `#[macro(attr = "value")]`
@@ -317,6 +638,24 @@ struct MacroAttributeArguments {
     enumerate: TOption<String>,
     #[darling(default)]
     state_constructors: TOption<String>,
+    /// Declares if constructors are to be generated for the initial states and possibly gives them a name.
+    #[darling(default)]
+    initial_constructors: TOption<String>,
+    /// Declares if the automaton fields are to be listed as shared state in the diagrams.
+    #[darling(default)]
+    shared_fields: bool,
+    /// Declares if a JSON description of the automaton is to be generated as a `const`.
+    #[darling(default)]
+    embed_automaton: bool,
+    /// Declares if an enumeration of the outgoing transitions is to be generated for each state.
+    #[darling(default)]
+    generate_transition_enum: bool,
+    /// Declares if a `StateId` enumeration and a `transition_table` function describing the automaton are to be generated.
+    #[darling(default)]
+    transition_table: bool,
+    /// Declares if `From` implementations are to be generated for the transitions into states without fields.
+    #[darling(default)]
+    generate_from: bool,
 }
 
 /// A value to `proc_macro2::TokenStream2` conversion.
@@ -342,13 +681,32 @@ impl IntoCompileError for Vec<Error> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+/// A transition between two states.
+///
+/// Transitions are identified by their `source` and `symbol` only;
+/// since the automaton is deterministic, a set of transitions cannot hold
+/// two transitions with the same `source` and `symbol` but different `destination`s.
+#[derive(Debug, Eq, Clone)]
 struct Transition {
     source: Ident,
+    // Not part of the identity.
     destination: Ident,
     symbol: Ident,
 }
 
+impl PartialEq for Transition {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source && self.symbol == other.symbol
+    }
+}
+
+impl Hash for Transition {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+        self.symbol.hash(state);
+    }
+}
+
 impl Transition {
     fn new(source: Ident, destination: Ident, symbol: Ident) -> Self {
         Self {
@@ -380,6 +738,10 @@ struct StateMachineInfo {
     /// Extracted from functions with a signature like `(State) -> State`.
     transitions: HashSet<Transition>,
 
+    /// Transitions which only take `self` and have no guard,
+    /// the ones which can back a `From` implementation.
+    plain_transitions: HashSet<Transition>,
+
     /// Set of initial states.
     /// Extracted from functions with a signature like `() -> State`.
     initial_states: HashMap<Ident, HashSet<Ident>>,
@@ -388,6 +750,9 @@ struct StateMachineInfo {
     /// Extracted from functions with a signature like `(State) -> ()`.
     final_states: HashMap<Ident, HashSet<Ident>>,
 
+    /// Functions declared by each state trait.
+    state_functions: HashMap<Ident, HashSet<Ident>>,
+
     pub intermediate_automaton: IntermediateGraph<Ident, Ident>,
 }
 
@@ -401,8 +766,10 @@ impl StateMachineInfo {
             non_det_transitions: HashMap::new(),
             used_non_det_transitions: HashSet::new(),
             transitions: HashSet::new(),
+            plain_transitions: HashSet::new(),
             initial_states: HashMap::new(),
             final_states: HashMap::new(),
+            state_functions: HashMap::new(),
         }
     }
 
@@ -466,6 +833,59 @@ impl StateMachineInfo {
         }
     }
 
+    fn insert_function(&mut self, state: Ident, function: Ident) {
+        self.state_functions
+            .entry(state)
+            .or_default()
+            .insert(function);
+    }
+
+    /// Return the function named `function` declared by the trait of `state`, if any.
+    fn declared_function(&self, state: &Ident, function: &Ident) -> Option<&Ident> {
+        self.state_functions.get(state)?.get(function)
+    }
+
+    /// Check for initial states whose trait declares a function named like the generated constructor,
+    /// which the constructor would shadow.
+    fn check_constructor_clashes(&self, constructor_ident: &Ident) -> Vec<Error> {
+        let mut clashes = self
+            .initial_states
+            .keys()
+            .filter(|state| self.det_states.contains_key(state))
+            .filter_map(|state| self.declared_function(state, constructor_ident))
+            .collect::<Vec<_>>();
+        // sort to keep the diagnostics order stable across compilations
+        clashes.sort_by_key(|ident| ident.to_string());
+        clashes
+            .into_iter()
+            .map(|ident| TypestateError::ConstructorClash(ident.clone()).into())
+            .collect()
+    }
+
+    /// Check for items clashing with the generated transition enumerations, i.e. states named like
+    /// the enumeration of another state and functions named `transitions`, which the generated method would shadow.
+    fn check_transition_enum_clashes(&self) -> Vec<Error> {
+        let transitions_ident = format_ident!("transitions");
+        let mut states = self.det_states.keys().collect::<Vec<_>>();
+        states.sort_by_key(|state| state.to_string());
+        let mut errors = vec![];
+        for state in states {
+            let enum_ident = format_ident!("{}Transition", state);
+            if let Some((ident, _)) = self.det_states.get_key_value(&enum_ident) {
+                errors
+                    .push(TypestateError::TransitionEnumClash(ident.clone(), state.clone()).into());
+            }
+            if let Some((ident, _)) = self.non_det_transitions.get_key_value(&enum_ident) {
+                errors
+                    .push(TypestateError::TransitionEnumClash(ident.clone(), state.clone()).into());
+            }
+            if let Some(ident) = self.declared_function(state, &transitions_ident) {
+                errors.push(TypestateError::TransitionsClash(ident.clone()).into());
+            }
+        }
+        errors
+    }
+
     fn insert_final(&mut self, state: Ident, transition: Ident) {
         if let Some(transitions) = self.final_states.get_mut(&state) {
             transitions.insert(transition);
@@ -486,7 +906,7 @@ impl Default for StateMachineInfo {
 enum TypestateError {
     MissingAutomata,
     NonProductiveState(Ident),
-    NonUsefulState(Ident),
+    UnreachableState(Ident),
     MissingInitialState,
     MissingFinalState,
     ConflictingAttributes(Attribute),
@@ -499,6 +919,12 @@ enum TypestateError {
     UnsupportedStruct(ItemStruct),
     UnsupportedState(Ident),
     UnusedTransition(Ident),
+    UnsupportedGuard(Attribute),
+    ConstructorClash(Ident),
+    GuardedTransitionClash(Ident),
+    TransitionEnumClash(Ident, Ident),
+    TransitionsClash(Ident),
+    UnsupportedMetadata(Path, &'static str),
 }
 
 impl From<TypestateError> for syn::Error {
@@ -506,7 +932,7 @@ impl From<TypestateError> for syn::Error {
         match err {
             TypestateError::MissingAutomata => Error::new(Span::call_site(), format!("Missing `#[{}]` struct.", AUTOMATA_ATTR_IDENT)),
             TypestateError::NonProductiveState(ident) => Error::new_spanned(ident, "Non-productive state. For a state to be productive, a path from the state to a final state is required to exist."),
-            TypestateError::NonUsefulState(ident) => Error::new_spanned(ident, "Non-useful state. For a state to be useful it must first be productive and a path from initial state to the state is required to exist."),
+            TypestateError::UnreachableState(ident) => Error::new_spanned(&ident, format!("Unreachable state. No initial state can reach `{}`, consider adding a transition into it or removing it.", ident)),
             TypestateError::MissingInitialState => Error::new(Span::call_site(), "Missing initial state. To declare an initial state you can use a function with signature like `fn f() -> T` where `T` is a declared state."),
             TypestateError::MissingFinalState => Error::new(Span::call_site(), "Missing final state. To declare a final state you can use a function with signature like `fn f(self) -> T` where `T` is not a declared state."),
             TypestateError::ConflictingAttributes(attr) => Error::new_spanned(attr, "Conflicting attributes are declared."), // TODO add which attributes are conflicting
@@ -519,6 +945,12 @@ impl From<TypestateError> for syn::Error {
             TypestateError::UnsupportedStruct(item_struct) => Error::new_spanned(&item_struct, "Tuple structures are not supported."),
             TypestateError::UnsupportedState(ident) => Error::new_spanned(&ident, "`enum` variants cannot refer to other `enum`s."),
             TypestateError::UnusedTransition(ident) => Error::new_spanned(&ident, "Unused transitions are not allowed."),
+            TypestateError::UnsupportedMetadata(key, supported) => Error::new_spanned(&key, format!("`{}` metadata has no effect here, it is only supported on {}.", key.to_token_stream(), supported)),
+            TypestateError::GuardedTransitionClash(ident) => Error::new_spanned(&ident, format!("`{}` clashes with the generated guarded version of a transition, which would shadow it. Consider renaming it.", ident)),
+            TypestateError::TransitionEnumClash(ident, state) => Error::new_spanned(&ident, format!("`{}` clashes with the generated enumeration of the transitions of `{}`. Consider renaming it.", ident, state)),
+            TypestateError::TransitionsClash(ident) => Error::new_spanned(&ident, format!("`{}` clashes with the generated method listing the transitions, which would shadow it. Consider renaming it.", ident)),
+            TypestateError::ConstructorClash(ident) => Error::new_spanned(&ident, format!("`{}` clashes with the generated initial constructor, which would shadow it. Consider renaming it or setting `initial_constructors = \"...\"`.", ident)),
+            TypestateError::UnsupportedGuard(attr) => Error::new_spanned(&attr, "Guards are only supported on transitions between states, i.e. `fn f(self) -> T` where `T` is a declared state."),
         }
     }
 }
@@ -529,3 +961,58 @@ impl IntoCompileError for TypestateError {
         err.to_compile_error()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Transition;
+    use proc_macro2::Span;
+    use std::collections::HashSet;
+    use syn::Ident;
+
+    fn ident(name: &str) -> Ident {
+        Ident::new(name, Span::call_site())
+    }
+
+    #[test]
+    fn transition_identity() {
+        // a state cannot declare the same function twice, so the destination is not part of the identity
+        let transitions: HashSet<_> = vec![
+            Transition::new(ident("A"), ident("B"), ident("f")),
+            Transition::new(ident("A"), ident("C"), ident("f")),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(transitions.len(), 1);
+    }
+
+    #[test]
+    #[cfg(any(feature = "dot", feature = "plantuml", feature = "html"))]
+    fn write_if_changed() {
+        use super::write_if_changed;
+        use std::{
+            fs,
+            time::{Duration, SystemTime},
+        };
+
+        let path = std::env::temp_dir().join(format!("typestate-{}.dot", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        fs::write(&path, "digraph {}").unwrap();
+        let past = SystemTime::UNIX_EPOCH + Duration::from_secs(1);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+
+        // the same contents leave the file untouched
+        write_if_changed(path_str, b"digraph {}").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), past);
+
+        write_if_changed(path_str, b"digraph { A }").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"digraph { A }");
+        assert_ne!(fs::metadata(&path).unwrap().modified().unwrap(), past);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -3,6 +3,7 @@ use syn::{visit_mut::VisitMut, Error, Fields, Ident, ItemEnum, ItemMod, Variant}
 
 use crate::{
     igraph::{Metadata, StateNode},
+    visitors::metadata_keys,
     StateMachineInfo, TypestateError,
 };
 
@@ -59,7 +60,19 @@ impl<'sm> DecisionVisitor<'sm> {
                     if attr.path.is_ident("metadata") {
                         match attr.parse_meta() {
                             Ok(meta) => match Metadata::from_meta(&meta) {
-                                Ok(metadata) => state.update_metadata(metadata),
+                                Ok(metadata) => {
+                                    // the weight only applies to transitions
+                                    for key in metadata_keys(&meta, &["weight"]) {
+                                        self.errors.push(
+                                            TypestateError::UnsupportedMetadata(
+                                                key.clone(),
+                                                "transitions, i.e. functions",
+                                            )
+                                            .into(),
+                                        );
+                                    }
+                                    state.update_metadata(metadata)
+                                }
                                 Err(err) => {
                                     // TODO fix this hack
                                     // HACK
@@ -1,3 +1,21 @@
 pub(crate) mod transition;
 pub(crate) mod decision;
-pub(crate) mod state;
\ No newline at end of file
+pub(crate) mod state;
+use syn::{Meta, NestedMeta, Path};
+
+/// Return the keys of a `#[metadata(...)]` attribute which are among `keys`,
+/// used to report the keys which have no effect where the attribute is placed.
+pub(crate) fn metadata_keys<'m>(meta: &'m Meta, keys: &[&str]) -> Vec<&'m Path> {
+    match meta {
+        Meta::List(list) => list
+            .nested
+            .iter()
+            .filter_map(|nested| match nested {
+                NestedMeta::Meta(meta) => Some(meta.path()),
+                NestedMeta::Lit(_) => None,
+            })
+            .filter(|path| keys.iter().any(|key| path.is_ident(key)))
+            .collect(),
+        _ => vec![],
+    }
+}
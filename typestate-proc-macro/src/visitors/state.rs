@@ -10,6 +10,7 @@ use syn::{
 
 pub(crate) const AUTOMATA_ATTR_IDENT: &str = "automaton";
 pub(crate) const STATE_ATTR_IDENT: &str = "state";
+pub(crate) const FINAL_STATE_ATTR_IDENT: &str = "final_state";
 
 type Result<Ok, Err = Error> = ::core::result::Result<Ok, Err>;
 
@@ -37,11 +38,8 @@ pub(crate) fn visit_states(
         return vec![TypestateError::MissingAutomata.into()];
     }
 
-    match &mut module.content {
-        Some((_, v)) => {
-            v.append(&mut sealed_trait.into()); // HACK unwrap is safe because otherwise errors would've bailed
-        }
-        None => {}
+    if let Some((_, v)) = &mut module.content {
+        v.append(&mut sealed_trait.into()); // HACK unwrap is safe because otherwise errors would've bailed
     }
 
     vec![]
@@ -214,6 +212,22 @@ impl<'sm> VisitMut for StateVisitor<'sm> {
                 // BOOK: intermediate_automaton.add_state
                 self.state_machine_info.intermediate_automaton.add_state(it_struct.ident.clone());
 
+                // `#[final_state]` explicitly marks the state as accepting
+                let attrs_len = it_struct.attrs.len();
+                it_struct
+                    .attrs
+                    .retain(|attr| !attr.path.is_ident(FINAL_STATE_ATTR_IDENT));
+                if it_struct.attrs.len() != attrs_len {
+                    // BOOK
+                    self.state_machine_info
+                        .intermediate_automaton
+                        .add_final_state(it_struct.ident.clone());
+                    self.state_machine_info
+                        .final_states
+                        .entry(it_struct.ident.clone())
+                        .or_default();
+                }
+
                 // TODO: remove the call below
                 self.state_machine_info.add_state(it_struct.clone().into());
                 self.sealed_trait.state_idents.push(it_struct.ident.clone());
@@ -1,9 +1,16 @@
 use std::collections::HashSet;
 
-use crate::{StateMachineInfo, Transition, TypestateError, CRATE_NAME, GENERATED_ATTR_IDENT};
+use crate::{
+    generated_attr,
+    igraph::{Metadata, Node, StateNode},
+    visitors::metadata_keys,
+    StateMachineInfo, Transition, TypestateError, CRATE_NAME, GENERATED_ATTR_IDENT,
+};
+use darling::FromMeta;
 use syn::{
-    visit_mut::VisitMut, Error, FnArg, Ident, ItemMod, ItemTrait, Receiver, ReturnType, Signature,
-    TraitItemMethod, Type,
+    ext::IdentExt, visit_mut::VisitMut, Attribute, Error, FnArg, GenericArgument, Ident, Item,
+    ItemMod, ItemTrait, Path, PathArguments, Receiver, ReturnType, Signature, TraitItem,
+    TraitItemMethod, Type, TypePath,
 };
 
 macro_rules! bail_if_any {
@@ -27,15 +34,48 @@ pub(crate) fn visit_transitions(
     transition_visitor.visit_item_mod_mut(module);
 
     // report transition_visitor errors and return
+    let mut clashes = transition_visitor.check_guarded_clashes();
+    transition_visitor.errors.append(&mut clashes);
     bail_if_any!(transition_visitor.errors);
+    if let Some((_, v)) = &mut module.content {
+        v.append(&mut transition_visitor.guarded_transitions);
+    }
     bail_if_any!(state_machine_info.check_missing());
     bail_if_any!(state_machine_info.check_unused_non_det_transitions());
     vec![]
 }
 
+/// Arguments of the `#[transition(...)]` attribute.
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+struct TransitionAttr {
+    /// Predicate (`fn(&Automaton<State>) -> bool`) which must hold for the transition to proceed.
+    guard: Option<Path>,
+    /// Skip the transition, removing it from the trait and the automaton.
+    ignore: bool,
+}
+
+impl TransitionAttr {
+    /// Check if the method is marked with `#[transition(ignore)]`.
+    /// Malformed attributes are not ignored, they are reported when visiting the method.
+    fn is_ignored(method: &TraitItemMethod) -> bool {
+        method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("transition"))
+            .filter_map(|attr| attr.parse_meta().ok())
+            .filter_map(|meta| TransitionAttr::from_meta(&meta).ok())
+            .any(|transition_attr| transition_attr.ignore)
+    }
+}
+
 struct TransitionVisitor<'sm> {
     current_state: Option<Ident>,
     state_machine_info: &'sm mut StateMachineInfo,
+    /// Guarded versions of the transitions, to be added to the module.
+    guarded_transitions: Vec<Item>,
+    /// States and names of the guarded versions of the transitions.
+    guarded_idents: Vec<(Ident, Ident)>,
     errors: Vec<Error>,
 }
 
@@ -44,6 +84,8 @@ impl<'sm> TransitionVisitor<'sm> {
         Self {
             current_state: None,
             state_machine_info,
+            guarded_transitions: vec![],
+            guarded_idents: vec![],
             errors: vec![],
         }
     }
@@ -58,6 +100,71 @@ impl<'sm> TransitionVisitor<'sm> {
         self.errors
             .push(TypestateError::InvalidAssocFuntions(it.clone()).into());
     }
+
+    /// Add `unsupported guard` error to the error vector.
+    fn push_unsupported_guard_error(&mut self, attr: &Attribute) {
+        self.errors
+            .push(TypestateError::UnsupportedGuard(attr.clone()).into());
+    }
+
+    /// Check for functions declared with the name of a guarded version of a transition,
+    /// which the guarded version would shadow.
+    fn check_guarded_clashes(&self) -> Vec<Error> {
+        self.guarded_idents
+            .iter()
+            .filter_map(|(state, try_fn_ident)| {
+                self.state_machine_info
+                    .declared_function(state, try_fn_ident)
+            })
+            .map(|ident| TypestateError::GuardedTransitionClash(ident.clone()).into())
+            .collect()
+    }
+
+    /// Generate a `try_` version of the transition which only proceeds if the `guard` holds,
+    /// otherwise the automaton is returned unchanged in the current state.
+    fn push_guarded_transition(&mut self, guard: &Path, sig: &Signature) {
+        let automaton_ident = self.state_machine_info.get_automaton_ident();
+        let state = self.current_state.as_ref().unwrap();
+        let trait_ident = ::quote::format_ident!("{}State", state);
+        let fn_ident = &sig.ident;
+        let try_fn_ident = ::quote::format_ident!("try_{}", fn_ident.unraw());
+        // the generics are forwarded, the arguments may depend on them
+        let (generics, _, where_clause) = sig.generics.split_for_impl();
+        let output = match &sig.output {
+            ReturnType::Type(_, ty) => ty,
+            ReturnType::Default => unreachable!("transitions return a state"),
+        };
+        // trait method arguments may be patterns, use our own identifiers instead
+        let (arg_ident, arg_ty): (Vec<_>, Vec<_>) = sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(pat_ty) => Some(&pat_ty.ty),
+                FnArg::Receiver(_) => None,
+            })
+            .enumerate()
+            .map(|(i, ty)| (::quote::format_ident!("__arg{}", i), ty))
+            .unzip();
+        let generated_attr = generated_attr();
+        self.guarded_transitions.push(::syn::parse_quote! {
+            #generated_attr
+            impl #automaton_ident<#state> {
+                pub fn #try_fn_ident #generics(
+                    self,
+                    #(#arg_ident: #arg_ty,)*
+                ) -> ::core::result::Result<#output, Self> #where_clause {
+                    if #guard(&self) {
+                        ::core::result::Result::Ok(
+                            <Self as #trait_ident>::#fn_ident(self, #(#arg_ident,)*)
+                        )
+                    } else {
+                        ::core::result::Result::Err(self)
+                    }
+                }
+            }
+        });
+        self.guarded_idents.push((state.clone(), try_fn_ident));
+    }
 }
 
 impl<'sm> VisitMut for TransitionVisitor<'sm> {
@@ -97,6 +204,11 @@ impl<'sm> VisitMut for TransitionVisitor<'sm> {
         if self.state_machine_info.det_states.contains_key(ident) {
             self.current_state = Some(ident.clone());
             i.ident = ::quote::format_ident!("{}State", ident);
+            // ignored transitions are removed before they are added to the automaton
+            i.items.retain(|item| match item {
+                TraitItem::Method(method) => !TransitionAttr::is_ignored(method),
+                _ => true,
+            });
             // go deeper
             for item in &mut i.items {
                 self.visit_trait_item_mut(item);
@@ -121,19 +233,79 @@ impl<'sm> VisitMut for TransitionVisitor<'sm> {
             });
         let fn_kind = sig.extract_signature_kind(&states);
         let fn_ident = sig.ident.clone();
+        if let Some(state) = &self.current_state {
+            self.state_machine_info
+                .insert_function(state.clone(), fn_ident.clone());
+        }
+
+        let mut metadata = Metadata::default();
+        attrs.retain(|attr| {
+            if attr.path.is_ident("metadata") {
+                match attr.parse_meta() {
+                    Ok(meta) => match Metadata::from_meta(&meta) {
+                        Ok(m) => {
+                            // the priority and guard only apply to the branches of a decision
+                            for key in metadata_keys(&meta, &["priority", "guard"]) {
+                                self.errors.push(
+                                    TypestateError::UnsupportedMetadata(
+                                        key.clone(),
+                                        "`enum` variants, i.e. decision branches",
+                                    )
+                                    .into(),
+                                );
+                            }
+                            metadata = m;
+                        }
+                        Err(err) => self.errors.push(Error::new_spanned(attr, err.to_string())),
+                    },
+                    Err(err) => self.errors.push(err),
+                }
+                false
+            } else {
+                true
+            }
+        });
+        let mut guard = None;
+        attrs.retain(|attr| {
+            if attr.path.is_ident("transition") {
+                match attr.parse_meta() {
+                    Ok(meta) => match TransitionAttr::from_meta(&meta) {
+                        Ok(transition_attr) => {
+                            guard = transition_attr.guard.map(|guard| (guard, attr.clone()));
+                        }
+                        Err(err) => self.errors.push(Error::new_spanned(attr, err.to_string())),
+                    },
+                    Err(err) => self.errors.push(err),
+                }
+                false
+            } else {
+                true
+            }
+        });
+        let transition = crate::igraph::Transition::new(fn_ident.clone())
+            .with_weight(metadata.weight())
+            .with_color(metadata.color());
+        // the label and note are attached to the destination, as the decision branches do
+        let destination = |state: Option<Ident>| {
+            let mut node = StateNode::new(state);
+            node.update_metadata(metadata.clone());
+            Node::State(node)
+        };
         sig.expand_signature_state(self.state_machine_info); // TODO check for correct expansion
 
+        if let Some((_, attr)) = &guard {
+            if !matches!(fn_kind, FnKind::Transition(_)) {
+                self.push_unsupported_guard_error(attr);
+            }
+        }
+
         match fn_kind {
             FnKind::Initial(return_ty_ident) => {
                 // add a transition to an initial state
                 // BOOK
                 self.state_machine_info
                     .intermediate_automaton
-                    .add_transition(
-                        None,
-                        fn_ident.clone().into(),
-                        return_ty_ident.clone().into(),
-                    );
+                    .add_transition(None, transition, destination(Some(return_ty_ident.clone())));
 
                 self.state_machine_info
                     .insert_initial(return_ty_ident, fn_ident);
@@ -146,25 +318,33 @@ impl<'sm> VisitMut for TransitionVisitor<'sm> {
                 // BOOK
                 self.state_machine_info
                     .intermediate_automaton
-                    .add_transition(Some(state.clone()), fn_ident.clone().into(), None.into());
+                    .add_transition(Some(state.clone()), transition, destination(None));
 
                 self.state_machine_info.insert_final(state, fn_ident);
             }
             FnKind::Transition(return_ty_ident) => {
                 // add #[must_use]
                 attrs.push(::syn::parse_quote!(#[must_use]));
+                if let Some((guard, _)) = &guard {
+                    self.push_guarded_transition(guard, sig);
+                }
                 let source = self.current_state.as_ref().unwrap().clone();
                 // BOOK
                 self.state_machine_info
                     .intermediate_automaton
                     .add_transition(
                         source.clone().into(),
-                        fn_ident.clone().into(),
-                        return_ty_ident.clone().into(),
+                        transition,
+                        destination(Some(return_ty_ident.clone())),
                     );
 
                 let transition = Transition::new(source, return_ty_ident.clone(), fn_ident);
 
+                if guard.is_none() && sig.inputs.len() == 1 && sig.generics.params.is_empty() {
+                    self.state_machine_info
+                        .plain_transitions
+                        .insert(transition.clone());
+                }
                 self.state_machine_info.transitions.insert(transition);
                 // mark non det transition as used
                 if self
@@ -185,8 +365,8 @@ impl<'sm> VisitMut for TransitionVisitor<'sm> {
                     .intermediate_automaton
                     .add_transition(
                         state.clone().into(),
-                        fn_ident.clone().into(),
-                        state.clone().into(),
+                        transition,
+                        destination(Some(state.clone())),
                     );
 
                 let transition = Transition::new(state.clone(), state.clone(), fn_ident);
@@ -234,6 +414,7 @@ enum ReceiverKind {
 /// Possible kinds are:
 /// - `()`
 /// - `State`
+/// - `Result<State, E>`
 /// - `T`
 #[derive(Debug)]
 enum OutputKind {
@@ -243,6 +424,8 @@ enum OutputKind {
     ///
     /// Note: `&T` or `&mut T` are not valid states.
     State(Ident),
+    /// Function returns a `Result<T, E>` where `T` is a valid state.
+    FallibleState(Ident),
     /// Any other `T`.
     Other,
 }
@@ -250,13 +433,13 @@ enum OutputKind {
 /// Enumeration describing a function's kind in regard to the typestate state machine.
 ///
 /// Possible kinds are:
-/// - `fn() -> State`
+/// - `fn() -> State` or `fn() -> Result<State, E>`
 /// - `fn(self) -> T`
 /// - `fn(self) -> State`
 /// - `fn(&self) -> T` or `fn(&mut self) -> T`
 #[derive(Debug)]
 enum FnKind {
-    /// Function that does not take `self` and returns a valid state.
+    /// Function that does not take `self` and returns a valid state (possibly inside a `Result`).
     Initial(Ident),
     /// Function that consumes `self` and does not return a valid state.
     Final,
@@ -316,6 +499,11 @@ impl SignatureKind for Signature {
                             return OutputKind::State(ident.clone());
                         }
                     }
+                    if let Some(ident) = path.extract_result_ok_ident() {
+                        if states.contains(ident) {
+                            return OutputKind::FallibleState(ident.clone());
+                        }
+                    }
                     OutputKind::Other
                 }
                 _ => OutputKind::Other,
@@ -331,12 +519,15 @@ impl SignatureKind for Signature {
             | (ReceiverKind::MutOwnedSelf, OutputKind::State(ident)) => FnKind::Transition(ident),
             (ReceiverKind::OwnedSelf, _) | (ReceiverKind::MutOwnedSelf, _) => FnKind::Final,
             (ReceiverKind::RefSelf, _) | (ReceiverKind::MutRefSelf, _) => FnKind::SelfTransition,
-            (ReceiverKind::Other, OutputKind::State(ident)) => FnKind::Initial(ident),
+            (ReceiverKind::Other, OutputKind::State(ident))
+            | (ReceiverKind::Other, OutputKind::FallibleState(ident)) => FnKind::Initial(ident),
             (ReceiverKind::Other, _) => FnKind::Other,
         }
     }
 
     fn expand_signature_state(&mut self, info: &StateMachineInfo) {
+        // only fallible initial states are expanded, `fn(self) -> Result<State, E>` is a final state
+        let expand_result = matches!(self.extract_receiver_kind(), ReceiverKind::Other);
         let fn_out = &mut self.output;
         let det_states = &info.det_states;
 
@@ -347,6 +538,46 @@ impl SignatureKind for Signature {
                         let automata_ident = info.get_automaton_ident();
                         path.path = ::syn::parse_quote!(#automata_ident<#ident>);
                     }
+                } else if expand_result {
+                    if let Some(ident) = path.extract_result_ok_ident().cloned() {
+                        if det_states.contains_key(&ident) {
+                            let automata_ident = info.get_automaton_ident();
+                            path.replace_result_ok(::syn::parse_quote!(#automata_ident<#ident>));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Provides utility methods to handle `Result<T, E>` return types.
+trait ResultPath {
+    /// Extract `T` from `Result<T, E>` if `T` is a single identifier.
+    fn extract_result_ok_ident(&self) -> Option<&Ident>;
+    /// Replace `T` in `Result<T, E>` with `ty`.
+    fn replace_result_ok(&mut self, ty: Type);
+}
+
+impl ResultPath for TypePath {
+    fn extract_result_ok_ident(&self) -> Option<&Ident> {
+        let segment = self.path.segments.last()?;
+        if segment.ident != "Result" {
+            return None;
+        }
+        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+            if let Some(GenericArgument::Type(Type::Path(ok))) = args.args.first() {
+                return ok.path.get_ident();
+            }
+        }
+        None
+    }
+
+    fn replace_result_ok(&mut self, ty: Type) {
+        if let Some(segment) = self.path.segments.last_mut() {
+            if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                if let Some(GenericArgument::Type(ok)) = args.args.first_mut() {
+                    *ok = ty;
                 }
             }
         }